@@ -1,23 +1,210 @@
 use anyhow::{Context, Result};
 use chrono::{DateTime, Local};
-use git2::{Repository, RepositoryInitOptions};
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use git2::{ObjectType, Repository, RepositoryInitOptions, TreeWalkMode, TreeWalkResult};
+use regex::Regex;
+use crate::remote::RemoteManager;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashSet};
 use std::fs;
-use std::path::{Path, PathBuf};
+use std::io;
+use std::path::{Component, Path, PathBuf};
+use std::process::Command;
+use std::time::{Duration, Instant};
+use walkdir::WalkDir;
 
 /// Repository information
-#[derive(Debug, Clone)]
+/// Serializes `path` as a string and `modified` as an RFC 3339 timestamp
+#[derive(Debug, Clone, Serialize)]
 pub struct RepoInfo {
     pub name: String,
     pub path: PathBuf,
     pub size: u64,
     pub modified: DateTime<Local>,
+    /// Latest author or committer time across all branch tips
+    pub last_commit: Option<DateTime<Local>>,
+    /// Newest mtime among loose refs and `packed-refs`, which change on push
+    pub last_ref_update: Option<DateTime<Local>>,
+    pub commits: Option<usize>,
+    /// No refs yet (nothing pushed), wherever HEAD points; known even when commits are not counted
+    pub empty: bool,
+    /// Directory entries skipped for lack of permission, so `size` is only a lower bound
+    pub unreadable: usize,
+    pub branch_count: usize,
+    pub tag_count: usize,
+    /// False for repositories created with a working tree
+    pub bare: bool,
+    /// URL of the repository's `origin` remote (set for mirrors)
+    pub origin_url: Option<String>,
+    /// Object stores borrowed via `objects/info/alternates` (set for forks); `size` excludes them
+    pub alternates: Vec<PathBuf>,
+    /// Why information could not be gathered (e.g., a corrupt repository)
+    pub error: Option<String>,
+    /// Time spent gathering git information, for profiling
+    #[serde(skip)]
+    pub timing: RepoTiming,
+}
+
+/// Time spent on git operations while gathering repository information
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RepoTiming {
+    /// Opening the repository
+    pub open: Duration,
+    /// Walking HEAD history to count commits
+    pub walk: Duration,
+}
+
+impl RepoTiming {
+    /// Total time spent on git operations
+    pub fn total(&self) -> Duration {
+        self.open + self.walk
+    }
+}
+
+/// Summary of the commit a ref points at
+#[derive(Debug, Clone)]
+pub struct RefSummary {
+    pub refname: String,
+    pub short_id: String,
     pub commits: Option<usize>,
+    pub author: String,
+    pub date: DateTime<Local>,
+}
+
+/// One reflog entry of a ref
+#[derive(Debug, Clone)]
+pub struct ReflogEntry {
+    pub old_id: String,
+    pub new_id: String,
+    pub committer: String,
+    pub date: DateTime<Local>,
+    pub message: String,
+}
+
+/// Divergence of a local branch from its `origin` counterpart
+#[derive(Debug, Clone)]
+pub struct BranchDivergence {
+    pub branch: String,
+    /// Commits ahead of and behind `refs/remotes/origin/<branch>`, None if it has no counterpart
+    pub ahead_behind: Option<(usize, usize)>,
+}
+
+/// Result of cloning a repository and checking the clone
+#[derive(Debug, Clone)]
+pub struct RestoreReport {
+    /// HEAD commit of the source repository
+    pub commit: String,
+    /// Whether the clone's HEAD matches the source's HEAD
+    pub head_matches: bool,
+    /// Whether `git fsck` passed on the clone
+    pub fsck_passed: bool,
+    /// Output of `git fsck` (empty when clean)
+    pub fsck_output: String,
+}
+
+impl RestoreReport {
+    /// Check if the restore test passed
+    pub fn passed(&self) -> bool {
+        self.head_matches && self.fsck_passed
+    }
+}
+
+/// How a repository's refs are stored on disk
+#[derive(Debug, Clone, Copy)]
+pub struct RefStorage {
+    /// Ref files under `refs/`
+    pub loose: usize,
+    /// Entries in `packed-refs`
+    pub packed: usize,
+}
+
+/// Commit whose message matched a search
+#[derive(Debug, Clone)]
+pub struct CommitMatch {
+    pub repo: String,
+    pub short_id: String,
+    pub summary: String,
+}
+
+/// Line in a repository file that matched a search
+#[derive(Debug, Clone)]
+pub struct GrepHit {
+    pub path: String,
+    pub line_number: usize,
+    pub line: String,
+}
+
+/// Permissions of new repositories on a hub shared by several users, as in `git init --shared`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SharedMode {
+    /// Permissions follow the umask, but git keeps the repository writable (core.sharedRepository=umask)
+    Umask,
+    /// Group-writable, new directories inherit the group (core.sharedRepository=group)
+    Group,
+    /// Group-writable and world-readable (core.sharedRepository=all)
+    All,
 }
 
+/// How repository sizes are measured
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SizeMode {
+    /// Sum of logical file lengths (consistent across platforms)
+    #[default]
+    Apparent,
+    /// Allocated disk blocks, reflecting compression and sparse files (Unix only)
+    Disk,
+}
+
+/// Which repository information to compute, since some fields are expensive
+#[derive(Debug, Clone, Copy)]
+pub struct InfoOptions {
+    /// How repository sizes are measured
+    pub size_mode: SizeMode,
+    /// Walk HEAD history to count commits (slow on large repositories)
+    pub count_commits: bool,
+}
+
+/// Catalog of repositories found anywhere below a directory, written by `index`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HubIndex {
+    pub generated: DateTime<Local>,
+    /// Repository paths relative to the indexed directory
+    pub repos: Vec<String>,
+}
+
+impl HubIndex {
+    /// Write to a temporary file next to `path` and rename it into place,
+    /// so a crash or concurrent run never leaves a partially written index
+    pub fn save_atomic(&self, path: &Path) -> Result<()> {
+        let dir = path.parent().context("Index path has no parent directory")?;
+        let mut file = tempfile::NamedTempFile::new_in(dir)
+            .context("Failed to create temporary index file")?;
+
+        serde_json::to_writer_pretty(&mut file, self)?;
+        file.as_file().sync_all()
+            .context("Failed to write index file")?;
+        file.persist(path)
+            .context("Failed to replace index file")?;
+
+        Ok(())
+    }
+}
+
+/// Name of the index file, stored in the indexed directory
+pub const INDEX_FILE: &str = ".local-git-rs-index.json";
+
+/// Maximum number of namespace levels above a repository
+/// (1 allows `team/project`, but not `org/team/project`)
+pub const MAX_NAMESPACE_DEPTH: usize = 1;
+
 /// Local Git repository manager
 /// Manages creation, deletion, and query of local bare repositories
 pub struct LocalGitHub {
     hub_path: PathBuf,
+    exact_names: bool,
+    shared: Option<SharedMode>,
 }
 
 impl LocalGitHub {
@@ -25,9 +212,24 @@ impl LocalGitHub {
     pub fn new<P: AsRef<Path>>(hub_path: P) -> Self {
         Self {
             hub_path: hub_path.as_ref().to_path_buf(),
+            exact_names: false,
+            shared: None,
         }
     }
 
+    /// Use repository names exactly as given, without appending the .git suffix
+    /// Useful to address on-disk names such as "foo.git.git" unambiguously
+    pub fn exact_names(mut self, exact: bool) -> Self {
+        self.exact_names = exact;
+        self
+    }
+
+    /// Create new repositories with shared permissions (None keeps git's private default)
+    pub fn shared(mut self, shared: Option<SharedMode>) -> Self {
+        self.shared = shared;
+        self
+    }
+
     /// Initialize hub directory
     /// Create directory if it doesn't exist
     pub fn init(&self) -> Result<()> {
@@ -41,51 +243,120 @@ impl LocalGitHub {
     /// Create new bare repository
     ///
     /// # Arguments
-    /// * `name` - Repository name (without .git suffix)
+    /// * `name` - Repository name (without .git suffix), optionally namespaced (e.g., "team/project")
     pub fn create_repo(&self, name: &str) -> Result<PathBuf> {
-        // Validate repository name
-        self.validate_repo_name(name)?;
+        self.init_repo(name, true)
+    }
 
-        let repo_name = if name.ends_with(".git") {
-            name.to_string()
+    /// Create new non-bare repository with a working tree
+    /// Stored as `<name>/` (no .git suffix) with an inner `.git` directory
+    ///
+    /// # Arguments
+    /// * `name` - Repository name, optionally namespaced (e.g., "team/project")
+    pub fn create_worktree_repo(&self, name: &str) -> Result<PathBuf> {
+        if name.ends_with(".git") {
+            anyhow::bail!("Repositories with a working tree cannot use the '.git' suffix");
+        }
+        self.init_repo(name, false)
+    }
+
+    /// Seed a repository with the branches and tags of a template, once
+    /// `source` is a hub repository name or any URL/path git can fetch from; no remote is configured
+    /// Returns the number of commits reachable from the seeded HEAD
+    /// With `verbose`, transfer progress, remote messages and ref updates are logged to stderr
+    pub fn seed_repo(&self, name: &str, source: &str, verbose: bool) -> Result<Option<usize>> {
+        let repo = Repository::open(self.get_repo_path(name)?)
+            .context("Failed to open repository")?;
+
+        let url = if self.repo_exists(source) {
+            self.get_repo_path(source)?.to_string_lossy().to_string()
         } else {
-            format!("{}.git", name)
+            source.to_string()
         };
 
-        let repo_path = self.hub_path.join(&repo_name);
+        let mut remote = repo.remote_anonymous(&url)
+            .context("Invalid template source")?;
 
-        if repo_path.exists() {
-            anyhow::bail!("Repository '{}' already exists", name);
+        let mut fetch_options = git2::FetchOptions::new();
+        if verbose {
+            eprintln!("fetch: {}", url);
+            fetch_options.remote_callbacks(verbose_callbacks());
         }
 
-        let mut opts = RepositoryInitOptions::new();
-        opts.bare(true);
-        opts.no_reinit(true);
+        remote
+            .fetch(
+                &["+refs/heads/*:refs/heads/*", "+refs/tags/*:refs/tags/*"],
+                Some(&mut fetch_options),
+                None,
+            )
+            .with_context(|| format!("Failed to fetch template '{}'", source))?;
 
-        Repository::init_opts(&repo_path, &opts)
-            .context("Failed to initialize bare repository")?;
+        // Follow the template's default branch
+        if let Ok(default_branch) = remote.default_branch()
+            && let Some(default_branch) = default_branch.as_str()
+        {
+            repo.set_head(default_branch)
+                .context("Failed to set HEAD")?;
+        }
 
-        Ok(repo_path)
+        // Populate the working tree of non-bare repositories
+        if !repo.is_bare() && repo.head().is_ok() {
+            repo.checkout_head(Some(git2::build::CheckoutBuilder::new().force()))
+                .context("Failed to check out seeded HEAD")?;
+        }
+
+        Ok(self.get_commit_count(&repo))
     }
 
-    /// List all repositories
-    pub fn list_repos(&self) -> Result<Vec<String>> {
-        if !self.hub_path.exists() {
-            return Ok(Vec::new());
+    /// Copy hooks from one hub repository into another
+    /// Sample hooks are skipped; copied hooks are made executable
+    /// Returns the number of hooks copied
+    pub fn copy_hooks(&self, from: &str, to: &str) -> Result<usize> {
+        let src = git_dir(&self.get_repo_path(from)?).join("hooks");
+        let dest = git_dir(&self.get_repo_path(to)?).join("hooks");
+
+        if !src.is_dir() {
+            return Ok(0);
         }
 
-        let mut repos = Vec::new();
+        fs::create_dir_all(&dest)
+            .context("Failed to create hooks directory")?;
 
-        for entry in fs::read_dir(&self.hub_path)
-            .context("Failed to read hub directory")?
-        {
+        let mut copied = 0;
+        for entry in fs::read_dir(&src).context("Failed to read hooks directory")? {
             let entry = entry?;
             let path = entry.path();
 
-            if path.is_dir() && path.extension().map_or(false, |e| e == "git") {
-                if let Some(name) = path.file_name() {
-                    repos.push(name.to_string_lossy().to_string());
-                }
+            if !path.is_file() || path.extension().is_some_and(|e| e == "sample") {
+                continue;
+            }
+
+            let target = dest.join(entry.file_name());
+            fs::copy(&path, &target)
+                .with_context(|| format!("Failed to copy hook '{}'", path.display()))?;
+
+            #[cfg(unix)]
+            {
+                use std::os::unix::fs::PermissionsExt;
+                fs::set_permissions(&target, fs::Permissions::from_mode(0o755))?;
+            }
+
+            copied += 1;
+        }
+
+        Ok(copied)
+    }
+
+    /// List all repositories
+    /// Namespaced repositories are returned as "team/project.git"
+    /// Namespaces that cannot be read are skipped; iterate `iter_repo_names` to report them
+    pub fn list_repos(&self) -> Result<Vec<String>> {
+        let mut repos = Vec::new();
+        for name in self.iter_repo_names() {
+            match name {
+                Ok(name) => repos.push(name),
+                Err(e) if e.is::<UnreadableNamespace>() => {}
+                Err(e) => return Err(e),
             }
         }
 
@@ -94,31 +365,156 @@ impl LocalGitHub {
     }
 
     /// List all repositories with detailed information
-    pub fn list_repos_with_info(&self) -> Result<Vec<RepoInfo>> {
-        if !self.hub_path.exists() {
-            return Ok(Vec::new());
+    /// Namespaces that cannot be read are listed as entries with `error` set
+    pub fn list_repos_with_info(&self, options: InfoOptions) -> Result<Vec<RepoInfo>> {
+        let mut repos = Vec::new();
+        for info in self.iter_repos(options) {
+            match info {
+                Ok(info) => repos.push(info),
+                Err(e) => match e.downcast_ref::<UnreadableNamespace>() {
+                    Some(namespace) => {
+                        let name = namespace.name.clone();
+                        repos.push(self.unavailable_repo_info(&name, e));
+                    }
+                    None => return Err(e),
+                },
+            }
+        }
+
+        repos.sort_by(|a, b| a.name.cmp(&b.name));
+        Ok(repos)
+    }
+
+    /// Write the entire hub directory (repositories and any other files) to a .tar.gz archive
+    /// Symlinks are stored as links. Returns the number of repositories and their total size
+    pub fn export_all(&self, output: &Path) -> Result<(usize, u64)> {
+        if !self.hub_path.is_dir() {
+            anyhow::bail!("Hub '{}' does not exist", self.hub_path.display());
+        }
+
+        // An archive inside the hub would end up containing itself
+        let hub = self.hub_path.canonicalize()?;
+        let output_dir = output
+            .parent()
+            .filter(|parent| !parent.as_os_str().is_empty())
+            .unwrap_or(Path::new("."))
+            .canonicalize()
+            .context("Output directory does not exist")?;
+        if output_dir.starts_with(&hub) {
+            anyhow::bail!("Archive must be written outside the hub directory");
+        }
+
+        let file = fs::File::create(output)
+            .with_context(|| format!("Failed to create '{}'", output.display()))?;
+        let mut archive = tar::Builder::new(GzEncoder::new(file, Compression::default()));
+        archive.follow_symlinks(false);
+        archive.append_dir_all(".", &self.hub_path)
+            .context("Failed to archive hub")?;
+        archive.into_inner()?.finish()?;
+
+        let repos = self.list_repos()?;
+        let (size, _) = self.get_dir_size(&self.hub_path, SizeMode::Apparent)?;
+
+        Ok((repos.len(), size))
+    }
+
+    /// Restore a hub archive written by `export_all` into the hub directory
+    /// The hub directory must not exist or be empty. Returns the number of restored repositories
+    pub fn import_all(&self, archive_path: &Path) -> Result<usize> {
+        if self.hub_path.exists() && fs::read_dir(&self.hub_path)?.next().is_some() {
+            anyhow::bail!("Destination '{}' is not empty", self.hub_path.display());
+        }
+
+        let file = fs::File::open(archive_path)
+            .with_context(|| format!("Failed to open '{}'", archive_path.display()))?;
+
+        fs::create_dir_all(&self.hub_path)
+            .context("Failed to create destination directory")?;
+        tar::Archive::new(GzDecoder::new(file))
+            .unpack(&self.hub_path)
+            .context("Failed to extract archive")?;
+
+        Ok(self.list_repos()?.len())
+    }
+
+    /// Recursively find repositories at any depth below the hub directory and write the index file
+    /// Repositories are not descended into, and symlinks are not followed
+    pub fn write_index(&self) -> Result<HubIndex> {
+        if !self.hub_path.is_dir() {
+            anyhow::bail!("Directory '{}' does not exist", self.hub_path.display());
         }
 
         let mut repos = Vec::new();
+        let mut walker = WalkDir::new(&self.hub_path).min_depth(1).into_iter();
 
-        for entry in fs::read_dir(&self.hub_path)
-            .context("Failed to read hub directory")?
-        {
-            let entry = entry?;
-            let path = entry.path();
+        while let Some(entry) = walker.next() {
+            let entry = entry.context("Failed to read directory")?;
+            if !entry.file_type().is_dir() {
+                continue;
+            }
 
-            if path.is_dir() && path.extension().map_or(false, |e| e == "git") {
-                if let Some(name) = path.file_name() {
-                    let name_str = name.to_string_lossy().to_string();
-                    if let Ok(info) = self.get_repo_info(&name_str) {
-                        repos.push(info);
-                    }
-                }
+            if self.is_valid_git_repo(entry.path())? {
+                repos.push(self.display_name(entry.path()));
+                walker.skip_current_dir();
             }
         }
 
-        repos.sort_by(|a, b| a.name.cmp(&b.name));
-        Ok(repos)
+        repos.sort();
+        let index = HubIndex {
+            generated: Local::now(),
+            repos,
+        };
+
+        index.save_atomic(&self.hub_path.join(INDEX_FILE))?;
+
+        Ok(index)
+    }
+
+    /// Read the index file written by `write_index`, if there is one
+    /// A corrupt index is moved aside to `<INDEX_FILE>.corrupt`, so the next `write_index` starts fresh
+    pub fn read_index(&self) -> Result<Option<HubIndex>> {
+        let path = self.hub_path.join(INDEX_FILE);
+        if !path.exists() {
+            return Ok(None);
+        }
+
+        let content = fs::read_to_string(&path)
+            .context("Failed to read index file")?;
+
+        match serde_json::from_str(&content) {
+            Ok(index) => Ok(Some(index)),
+            Err(e) => {
+                let backup = path.with_extension("json.corrupt");
+                fs::rename(&path, &backup)
+                    .context("Failed to move corrupt index file aside")?;
+                Err(e).context(format!("Index file was corrupt and has been moved to '{}'", backup.display()))
+            }
+        }
+    }
+
+    /// Lazily iterate over repository names as the hub directory is read
+    /// Names are yielded in directory order, not sorted
+    pub fn iter_repo_names(&self) -> RepoNames {
+        RepoNames::new(&self.hub_path)
+    }
+
+    /// Lazily iterate over repositories with detailed information
+    /// Each repository is inspected only when the iterator reaches it, so callers can short-circuit
+    /// Repositories whose information cannot be gathered are yielded with `error` set
+    pub fn iter_repos(&self, options: InfoOptions) -> impl Iterator<Item = Result<RepoInfo>> + '_ {
+        self.iter_repo_names().map(move |name| {
+            let name = name?;
+            Ok(self
+                .get_repo_info(&name, options)
+                .unwrap_or_else(|e| self.unavailable_repo_info(&name, e)))
+        })
+    }
+
+    /// Check if repository has a working tree (non-bare)
+    pub fn is_worktree_repo(&self, name: &str) -> bool {
+        self.resolve_repo_path(name)
+            .map(|path| path.join(".git").is_dir())
+            .unwrap_or(false)
     }
 
     /// Search repositories by name pattern
@@ -134,176 +530,1969 @@ impl LocalGitHub {
         Ok(filtered)
     }
 
-    /// Delete repository with safety checks
-    pub fn delete_repo(&self, name: &str) -> Result<()> {
-        let repo_name = if name.ends_with(".git") {
-            name.to_string()
-        } else {
-            format!("{}.git", name)
+    /// Search repositories whose README at HEAD contains pattern (case-insensitive)
+    /// Repositories without commits or without a README are skipped
+    /// `progress` is called with (index, total, name) before each repository is read
+    pub fn search_readme(&self, pattern: &str, mut progress: impl FnMut(usize, usize, &str)) -> Result<Vec<String>> {
+        let all_repos = self.list_repos()?;
+        let pattern_lower = pattern.to_lowercase();
+
+        let mut matches = Vec::new();
+        for (i, name) in all_repos.iter().enumerate() {
+            progress(i, all_repos.len(), name);
+            if self.readme_contains(name, &pattern_lower) {
+                matches.push(name.clone());
+            }
+        }
+
+        Ok(matches)
+    }
+
+    /// Check if any top-level README* file at HEAD contains the lowercase pattern
+    fn readme_contains(&self, name: &str, pattern_lower: &str) -> bool {
+        let Ok(repo_path) = self.resolve_repo_path(name) else {
+            return false;
+        };
+        let Ok(repo) = Repository::open(&repo_path) else {
+            return false;
+        };
+        let Ok(tree) = repo.head().and_then(|head| head.peel_to_tree()) else {
+            return false;
         };
 
-        let repo_path = self.hub_path.join(&repo_name);
+        tree.iter()
+            .filter(|entry| {
+                entry.kind() == Some(ObjectType::Blob)
+                    && entry.name().is_some_and(|n| n.to_lowercase().starts_with("readme"))
+            })
+            .filter_map(|entry| repo.find_blob(entry.id()).ok())
+            .filter(|blob| !blob.is_binary())
+            .any(|blob| String::from_utf8_lossy(blob.content()).to_lowercase().contains(pattern_lower))
+    }
 
-        if !repo_path.exists() {
-            anyhow::bail!("Repository '{}' does not exist", name);
-        }
+    /// Search repositories whose HEAD commit author matches pattern
+    /// Matches author name or email (case-insensitive); repositories without commits are skipped
+    pub fn search_by_author(&self, pattern: &str) -> Result<Vec<String>> {
+        let all_repos = self.list_repos()?;
+        let pattern_lower = pattern.to_lowercase();
 
-        // Additional safety check: verify it's actually a git repository
-        if !self.is_valid_git_repo(&repo_path)? {
-            anyhow::bail!("Path '{}' is not a valid Git repository", repo_path.display());
-        }
+        let filtered: Vec<String> = all_repos
+            .into_iter()
+            .filter(|name| {
+                let repo_path = match self.resolve_repo_path(name) {
+                    Ok(path) => path,
+                    Err(_) => return false,
+                };
+                let repo = match Repository::open(&repo_path) {
+                    Ok(repo) => repo,
+                    Err(_) => return false,
+                };
+                let commit = match repo.head().and_then(|head| head.peel_to_commit()) {
+                    Ok(commit) => commit,
+                    Err(_) => return false,
+                };
 
-        fs::remove_dir_all(&repo_path)
-            .context("Failed to delete repository")?;
+                let author = commit.author();
+                [author.name(), author.email()]
+                    .into_iter()
+                    .flatten()
+                    .any(|field| field.to_lowercase().contains(&pattern_lower))
+            })
+            .collect();
 
-        Ok(())
+        Ok(filtered)
     }
 
-    /// Get repository information
-    pub fn get_repo_info(&self, name: &str) -> Result<RepoInfo> {
-        let repo_name = if name.ends_with(".git") {
-            name.to_string()
-        } else {
-            format!("{}.git", name)
+    /// Search commit messages in one repository, walking the history of all refs
+    /// Repositories that cannot be opened or have no commits yield no matches; a ref or commit
+    /// whose objects are missing is an error, so callers searching the hub should skip the repository
+    pub fn search_commit_messages(&self, name: &str, pattern: &Regex) -> Result<Vec<CommitMatch>> {
+        let repo_path = self.get_repo_path(name)?;
+        let repo = match Repository::open(&repo_path) {
+            Ok(repo) => repo,
+            Err(_) => return Ok(Vec::new()),
         };
 
-        let repo_path = self.hub_path.join(&repo_name);
+        let mut revwalk = repo.revwalk().context("Failed to walk history")?;
+        revwalk.push_glob("*").context("Failed to walk refs")?;
 
-        if !repo_path.exists() {
-            anyhow::bail!("Repository '{}' does not exist", name);
-        }
+        let mut matches = Vec::new();
 
-        // Get repository size
-        let size = self.get_dir_size(&repo_path)?;
+        for oid in revwalk {
+            let commit = repo.find_commit(oid?)?;
+            let message = String::from_utf8_lossy(commit.message_bytes());
 
-        // Get modification time
-        let metadata = fs::metadata(&repo_path)?;
-        let modified: DateTime<Local> = metadata.modified()?.into();
+            if pattern.is_match(&message) {
+                let short_id = commit
+                    .as_object()
+                    .short_id()?
+                    .as_str()
+                    .unwrap_or_default()
+                    .to_string();
 
-        // Get commit count
-        let commits = self.get_commit_count(&repo_path);
+                matches.push(CommitMatch {
+                    repo: self.display_name(&repo_path),
+                    short_id,
+                    summary: commit.summary().unwrap_or_default().to_string(),
+                });
+            }
+        }
 
-        Ok(RepoInfo {
-            name: repo_name,
-            path: repo_path,
-            size,
-            modified,
-            commits,
-        })
+        Ok(matches)
     }
 
-    /// Get full path of repository
-    pub fn get_repo_path(&self, name: &str) -> Result<PathBuf> {
-        let repo_name = if name.ends_with(".git") {
-            name.to_string()
-        } else {
-            format!("{}.git", name)
-        };
+    /// Search file contents at a revision without checking out
+    /// Binary files (containing a NUL byte) are skipped
+    ///
+    /// # Arguments
+    /// * `name` - Repository name
+    /// * `pattern` - Pattern to match against each line
+    /// * `rev` - Revision to search (default: HEAD)
+    pub fn grep(&self, name: &str, pattern: &Regex, rev: Option<&str>) -> Result<Vec<GrepHit>> {
+        let repo_path = self.get_repo_path(name)?;
+        let repo = Repository::open(&repo_path)
+            .context("Failed to open repository")?;
 
-        let repo_path = self.hub_path.join(&repo_name);
+        let rev = rev.unwrap_or("HEAD");
+        let tree = repo
+            .revparse_single(rev)
+            .and_then(|object| object.peel_to_tree())
+            .with_context(|| format!("Failed to resolve revision '{}'", rev))?;
 
-        if !repo_path.exists() {
-            anyhow::bail!("Repository '{}' does not exist", name);
+        let mut hits = Vec::new();
+        let mut walk_error = None;
+
+        tree.walk(TreeWalkMode::PreOrder, |root, entry| {
+            if entry.kind() != Some(ObjectType::Blob) {
+                return TreeWalkResult::Ok;
+            }
+
+            let blob = match repo.find_blob(entry.id()) {
+                Ok(blob) => blob,
+                Err(e) => {
+                    walk_error = Some(e);
+                    return TreeWalkResult::Abort;
+                }
+            };
+
+            // Same heuristic as git: a NUL byte in the first 8000 bytes means binary
+            let content = blob.content();
+            if content.iter().take(8000).any(|&b| b == 0) {
+                return TreeWalkResult::Ok;
+            }
+
+            let path = format!("{}{}", root, entry.name().unwrap_or_default());
+            for (i, line) in String::from_utf8_lossy(content).lines().enumerate() {
+                if pattern.is_match(line) {
+                    hits.push(GrepHit {
+                        path: path.clone(),
+                        line_number: i + 1,
+                        line: line.to_string(),
+                    });
+                }
+            }
+
+            TreeWalkResult::Ok
+        })?;
+
+        if let Some(e) = walk_error {
+            return Err(e).context("Failed to read file contents");
         }
 
-        Ok(repo_path)
+        Ok(hits)
     }
 
-    /// Check if repository exists
-    pub fn repo_exists(&self, name: &str) -> bool {
-        let repo_name = if name.ends_with(".git") {
-            name.to_string()
-        } else {
-            format!("{}.git", name)
-        };
+    /// Size of objects reachable only from each local branch and not from HEAD (the default branch)
+    /// Sizes are uncompressed object sizes, sorted largest first; the HEAD branch itself is omitted
+    pub fn branch_sizes(&self, name: &str) -> Result<Vec<(String, u64)>> {
+        let repo_path = self.get_repo_path(name)?;
+        let repo = Repository::open(&repo_path)
+            .context("Failed to open repository")?;
+        let odb = repo.odb().context("Failed to open object database")?;
 
-        self.hub_path.join(&repo_name).exists()
-    }
+        let head = repo.head().ok();
+        let head_oid = head.as_ref().and_then(|head| head.target());
+        let head_name = head.as_ref().and_then(|head| head.name()).map(str::to_string);
 
-    /// Validate repository name
-    fn validate_repo_name(&self, name: &str) -> Result<()> {
-        if name.is_empty() {
-            anyhow::bail!("Repository name cannot be empty");
-        }
+        let base = match head_oid {
+            Some(oid) => collect_objects(&repo, oid, None, &HashSet::new())?,
+            None => HashSet::new(),
+        };
 
-        // Check for invalid characters
-        let invalid_chars = ['/', '\\', ':', '*', '?', '"', '<', '>', '|'];
-        for c in invalid_chars {
-            if name.contains(c) {
-                anyhow::bail!("Repository name cannot contain '{}'", c);
+        let mut sizes = Vec::new();
+        for branch in repo.branches(Some(git2::BranchType::Local)).context("Failed to list branches")? {
+            let (branch, _) = branch?;
+            let reference = branch.get();
+            if reference.name().map(str::to_string) == head_name {
+                continue;
             }
-        }
+            let (Some(branch_name), Some(tip)) = (branch.name()?, reference.target()) else {
+                continue;
+            };
 
-        // Check for reserved names
-        if name == "." || name == ".." {
-            anyhow::bail!("Repository name cannot be '.' or '..'");
-        }
+            let unique = collect_objects(&repo, tip, head_oid, &base)?;
+            let mut size = 0;
+            for oid in unique {
+                size += odb.read_header(oid).context("Failed to read object")?.0 as u64;
+            }
 
-        // Check length
-        if name.len() > 255 {
-            anyhow::bail!("Repository name is too long (max 255 characters)");
+            sizes.push((branch_name.to_string(), size));
         }
 
-        Ok(())
+        sizes.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        Ok(sizes)
     }
 
-    /// Calculate directory size recursively
-    fn get_dir_size(&self, path: &Path) -> Result<u64> {
-        let mut total = 0;
+    /// Read the reflog of a ref, newest entry first
+    /// Bare repositories only keep reflogs when `core.logAllRefUpdates` is enabled
+    pub fn read_reflog(&self, name: &str, refname: &str) -> Result<Vec<ReflogEntry>> {
+        let repo_path = self.get_repo_path(name)?;
+        let repo = Repository::open(&repo_path)
+            .context("Failed to open repository")?;
 
-        if path.is_dir() {
-            for entry in fs::read_dir(path)
-                .context("Failed to read directory")?
-            {
-                let entry = entry?;
-                let entry_path = entry.path();
+        let reflog = repo.reflog(refname)
+            .with_context(|| format!("Failed to read reflog of '{}'", refname))?;
 
-                if entry_path.is_dir() {
-                    total += self.get_dir_size(&entry_path)?;
-                } else {
-                    total += entry.metadata()?.len();
+        let entries = reflog
+            .iter()
+            .map(|entry| {
+                let committer = entry.committer();
+                ReflogEntry {
+                    old_id: entry.id_old().to_string()[..7].to_string(),
+                    new_id: entry.id_new().to_string()[..7].to_string(),
+                    committer: committer.name().unwrap_or("unknown").to_string(),
+                    date: DateTime::from_timestamp(committer.when().seconds(), 0)
+                        .unwrap_or_default()
+                        .with_timezone(&Local),
+                    message: entry.message().unwrap_or_default().to_string(),
                 }
-            }
-        }
+            })
+            .collect();
 
-        Ok(total)
+        Ok(entries)
     }
 
-    /// Check if path is a valid Git repository
-    fn is_valid_git_repo(&self, path: &Path) -> Result<bool> {
-        let head_path = path.join("HEAD");
-        let objects_path = path.join("objects");
-        let refs_path = path.join("refs");
+    /// Check if a repository has no commits yet (unborn HEAD and no refs)
+    /// Repositories that cannot be opened are not considered empty
+    pub fn is_empty_repo(&self, name: &str) -> bool {
+        self.get_repo_path(name)
+            .ok()
+            .and_then(|path| Repository::open(path).ok())
+            .is_some_and(|repo| is_empty(&repo))
+    }
 
-        Ok(head_path.exists() && objects_path.exists() && refs_path.exists())
+    /// Check if ref updates are logged (`core.logAllRefUpdates`, which defaults to off for bare repositories)
+    pub fn reflog_enabled(&self, name: &str) -> Result<bool> {
+        let repo = Repository::open(self.get_repo_path(name)?)
+            .context("Failed to open repository")?;
+        let config = repo.config().context("Failed to open config")?;
+
+        Ok(match config.get_string("core.logAllRefUpdates") {
+            Ok(value) => matches!(value.to_lowercase().as_str(), "true" | "always" | "yes" | "on" | "1"),
+            Err(_) => !repo.is_bare(),
+        })
     }
 
-    /// Get commit count from repository
-    fn get_commit_count(&self, path: &Path) -> Option<usize> {
-        match Repository::open(path) {
-            Ok(repo) => {
-                match repo.revparse_single("HEAD") {
-                    Ok(commit) => {
-                        match commit.as_commit() {
-                            Some(c) => {
-                                // Count commits in the history
-                                let mut count = 0;
-                                let mut revwalk = match repo.revwalk() {
-                                    Ok(w) => w,
-                                    Err(_) => return None,
-                                };
-                                if revwalk.push(c.id()).is_ok() {
-                                    count = revwalk.count();
-                                }
-                                Some(count)
-                            }
-                            None => None,
-                        }
-                    }
-                    Err(_) => None,
+    /// Enable reflogs for all ref updates, so overwritten branch tips can be recovered
+    pub fn enable_reflog(&self, name: &str) -> Result<()> {
+        let repo = Repository::open(self.get_repo_path(name)?)
+            .context("Failed to open repository")?;
+
+        repo.config()
+            .and_then(|mut config| config.set_bool("core.logAllRefUpdates", true))
+            .context("Failed to enable reflog")?;
+
+        Ok(())
+    }
+
+    /// Replace git's placeholder `description` file (shown by gitweb and cgit)
+    pub fn set_description(&self, name: &str, description: &str) -> Result<()> {
+        let path = git_dir(&self.get_repo_path(name)?).join("description");
+
+        fs::write(&path, format!("{}\n", description.trim_end()))
+            .context("Failed to write description")?;
+
+        Ok(())
+    }
+
+    /// Compare local branches with their already-fetched `refs/remotes/origin/*` counterparts
+    /// Nothing is fetched, so the result is only as fresh as the last fetch
+    pub fn upstream_divergence(&self, name: &str) -> Result<Vec<BranchDivergence>> {
+        let repo_path = self.get_repo_path(name)?;
+        let repo = Repository::open(&repo_path)
+            .context("Failed to open repository")?;
+
+        let mut divergence = Vec::new();
+
+        for branch in repo.branches(Some(git2::BranchType::Local)).context("Failed to list branches")? {
+            let (branch, _) = branch?;
+            let (Some(branch_name), Some(local)) = (branch.name()?, branch.get().target()) else {
+                continue;
+            };
+
+            let upstream = repo
+                .refname_to_id(&format!("refs/remotes/origin/{}", branch_name))
+                .ok();
+            let ahead_behind = match upstream {
+                Some(upstream) => Some(
+                    repo.graph_ahead_behind(local, upstream)
+                        .context("Failed to compare branch with upstream")?,
+                ),
+                None => None,
+            };
+
+            divergence.push(BranchDivergence {
+                branch: branch_name.to_string(),
+                ahead_behind,
+            });
+        }
+
+        divergence.sort_by(|a, b| a.branch.cmp(&b.branch));
+        Ok(divergence)
+    }
+
+    /// Summarize history at a branch, tag or other revision
+    /// Errors with the list of available refs when `refname` cannot be resolved
+    pub fn ref_summary(&self, name: &str, refname: &str) -> Result<RefSummary> {
+        let repo_path = self.get_repo_path(name)?;
+        let repo = Repository::open(&repo_path)
+            .context("Failed to open repository")?;
+
+        let commit = match repo.revparse_single(refname).and_then(|object| object.peel_to_commit()) {
+            Ok(commit) => commit,
+            Err(_) => {
+                let mut available: Vec<String> = repo
+                    .references()
+                    .context("Failed to list refs")?
+                    .flatten()
+                    .filter_map(|reference| reference.shorthand().map(str::to_string))
+                    .collect();
+                available.sort();
+
+                if available.is_empty() {
+                    anyhow::bail!("Ref '{}' not found (repository has no refs)", refname);
+                }
+                anyhow::bail!("Ref '{}' not found. Available refs: {}", refname, available.join(", "));
+            }
+        };
+
+        let author = commit.author();
+        let author = match (author.name(), author.email()) {
+            (Some(name), Some(email)) => format!("{} <{}>", name, email),
+            (Some(name), None) => name.to_string(),
+            (None, Some(email)) => format!("<{}>", email),
+            (None, None) => "unknown".to_string(),
+        };
+
+        let date = DateTime::from_timestamp(commit.time().seconds(), 0)
+            .context("Commit has an invalid timestamp")?
+            .with_timezone(&Local);
+
+        Ok(RefSummary {
+            refname: refname.to_string(),
+            short_id: commit.as_object().short_id()?.as_str().unwrap_or_default().to_string(),
+            commits: count_commits(&repo, commit.id()),
+            author,
+            date,
+        })
+    }
+
+    /// Verify a repository can be restored by cloning it into a temporary directory
+    /// Runs `git fsck` on the clone and compares HEAD with the source; the clone is removed afterwards
+    pub fn test_restore(&self, name: &str) -> Result<RestoreReport> {
+        let repo_path = self.get_repo_path(name)?;
+        let source = Repository::open(&repo_path)
+            .context("Failed to open repository")?;
+
+        if is_empty(&source) {
+            anyhow::bail!("Repository is empty, there are no commits to verify");
+        }
+
+        let source_head = source
+            .head()
+            .and_then(|head| head.peel_to_commit())
+            .context("Repository has no commits to verify")?
+            .id();
+
+        let temp_dir = tempfile::tempdir()
+            .context("Failed to create temporary directory")?;
+        let clone_path = temp_dir.path().join("restore");
+
+        let clone = Repository::clone(&repo_path.to_string_lossy(), &clone_path)
+            .context("Failed to clone repository")?;
+        let clone_head = clone
+            .head()
+            .and_then(|head| head.peel_to_commit())
+            .context("Clone has no HEAD commit")?
+            .id();
+
+        // libgit2 has no fsck, use the git command line
+        let fsck = Command::new("git")
+            .args(["fsck", "--full"])
+            .current_dir(&clone_path)
+            .output()
+            .context("Failed to run 'git fsck' (is git installed?)")?;
+
+        let mut fsck_output = String::from_utf8_lossy(&fsck.stdout).to_string();
+        fsck_output.push_str(&String::from_utf8_lossy(&fsck.stderr));
+
+        Ok(RestoreReport {
+            commit: source_head.to_string(),
+            head_matches: clone_head == source_head,
+            fsck_passed: fsck.status.success(),
+            fsck_output: fsck_output.trim().to_string(),
+        })
+    }
+
+    /// Count loose refs and packed-refs entries
+    pub fn ref_storage(&self, name: &str) -> Result<RefStorage> {
+        let git_dir = git_dir(&self.get_repo_path(name)?);
+
+        let mut loose = 0;
+        let mut pending = vec![git_dir.join("refs")];
+        while let Some(dir) = pending.pop() {
+            if !dir.is_dir() {
+                continue;
+            }
+            for entry in fs::read_dir(&dir)
+                .context("Failed to read refs directory")?
+            {
+                let entry = entry?;
+                if entry.file_type()?.is_dir() {
+                    pending.push(entry.path());
+                } else {
+                    loose += 1;
+                }
+            }
+        }
+
+        // Skip the header comment and peeled-tag lines ("^<oid>")
+        let packed = match fs::read_to_string(git_dir.join("packed-refs")) {
+            Ok(content) => content
+                .lines()
+                .filter(|line| !line.is_empty() && !line.starts_with('#') && !line.starts_with('^'))
+                .count(),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => 0,
+            Err(e) => return Err(e).context("Failed to read packed-refs"),
+        };
+
+        Ok(RefStorage { loose, packed })
+    }
+
+    /// Look up a ref by full or short name (e.g., "refs/pull/1/head" or "main")
+    /// Returns the full ref name and the object id it points at
+    pub fn find_ref(&self, name: &str, refname: &str) -> Result<(String, git2::Oid)> {
+        if refname == "HEAD" {
+            anyhow::bail!("Name the branch instead of HEAD");
+        }
+
+        let repo = Repository::open(self.get_repo_path(name)?)
+            .context("Failed to open repository")?;
+
+        let reference = repo.resolve_reference_from_short_name(refname)
+            .with_context(|| format!("Ref '{}' not found", refname))?;
+        let full_name = reference.name()
+            .context("Ref name is not valid UTF-8")?
+            .to_string();
+        let oid = reference.target()
+            .with_context(|| format!("'{}' is a symbolic ref", full_name))?;
+
+        Ok((full_name, oid))
+    }
+
+    /// Delete a ref without cloning the repository
+    /// Returns the full ref name and the object id it pointed at, for recovery with `git update-ref`
+    pub fn delete_ref(&self, name: &str, refname: &str) -> Result<(String, git2::Oid)> {
+        let (full_name, oid) = self.find_ref(name, refname)?;
+        let repo = Repository::open(self.get_repo_path(name)?)
+            .context("Failed to open repository")?;
+
+        repo.find_reference(&full_name)
+            .and_then(|mut reference| reference.delete())
+            .with_context(|| format!("Failed to delete ref '{}'", full_name))?;
+
+        Ok((full_name, oid))
+    }
+
+    /// Rename a ref; a new name without `refs/` replaces only the last component of the old name,
+    /// so it stays in the old ref's namespace (e.g., `refs/tags/v1` to `refs/tags/v2`)
+    /// HEAD follows the rename when it pointed at the old ref, like `git branch -m`
+    /// Returns the old and new full ref names
+    pub fn rename_ref(&self, name: &str, old: &str, new: &str) -> Result<(String, String)> {
+        let (old_name, _) = self.find_ref(name, old)?;
+        let repo = Repository::open(self.get_repo_path(name)?)
+            .context("Failed to open repository")?;
+
+        let new_name = if new.starts_with("refs/") {
+            new.to_string()
+        } else {
+            let (namespace, _) = old_name
+                .rsplit_once('/')
+                .with_context(|| format!("Ref '{}' has no namespace", old_name))?;
+            format!("{}/{}", namespace, new)
+        };
+
+        if !git2::Reference::is_valid_name(&new_name) {
+            anyhow::bail!("'{}' is not a valid ref name", new_name);
+        }
+
+        let head_target = repo
+            .find_reference("HEAD")
+            .ok()
+            .and_then(|head| head.symbolic_target().map(str::to_string));
+
+        repo.find_reference(&old_name)
+            .and_then(|mut reference| {
+                reference.rename(&new_name, false, &format!("local-git-rs: renamed {} to {}", old_name, new_name))
+            })
+            .with_context(|| format!("Failed to rename '{}' to '{}'", old_name, new_name))?;
+
+        if head_target.as_deref() == Some(old_name.as_str()) {
+            repo.set_head(&new_name)
+                .context("Failed to point HEAD at the renamed ref")?;
+        }
+
+        Ok((old_name, new_name))
+    }
+
+    /// Pack all loose refs into `packed-refs` with `git pack-refs --all`
+    /// Returns ref storage before and after packing
+    pub fn pack_refs(&self, name: &str) -> Result<(RefStorage, RefStorage)> {
+        let repo_path = self.get_repo_path(name)?;
+        let before = self.ref_storage(name)?;
+
+        let output = Command::new("git")
+            .arg("--git-dir")
+            .arg(git_dir(&repo_path))
+            .args(["pack-refs", "--all"])
+            .output()
+            .context("Failed to run 'git pack-refs' (is git installed?)")?;
+
+        if !output.status.success() {
+            anyhow::bail!("git pack-refs failed: {}", String::from_utf8_lossy(&output.stderr).trim());
+        }
+
+        let after = self.ref_storage(name)?;
+        Ok((before, after))
+    }
+
+    /// Write a commit-graph file covering all reachable commits
+    /// Speeds up history walks in git (and in libgit2 where it reads the graph)
+    pub fn write_commit_graph(&self, name: &str) -> Result<()> {
+        let repo_path = self.get_repo_path(name)?;
+
+        let output = Command::new("git")
+            .arg("--git-dir")
+            .arg(git_dir(&repo_path))
+            .args(["commit-graph", "write", "--reachable"])
+            .output()
+            .context("Failed to run 'git commit-graph' (is git installed?)")?;
+
+        if !output.status.success() {
+            anyhow::bail!("git commit-graph failed: {}", String::from_utf8_lossy(&output.stderr).trim());
+        }
+
+        Ok(())
+    }
+
+    /// Check if a repository has a commit-graph file (single file or split chain)
+    pub fn has_commit_graph(&self, name: &str) -> Result<bool> {
+        let info_dir = git_dir(&self.get_repo_path(name)?).join("objects").join("info");
+
+        Ok(info_dir.join("commit-graph").is_file()
+            || info_dir.join("commit-graphs").join("commit-graph-chain").is_file())
+    }
+
+    /// Add a remote on one hub repository pointing at another, for chained backups
+    /// Returns the name of the remote that was added (see `RemoteManager::add_local_remote`)
+    pub fn add_inter_hub_remote(
+        &self,
+        source: &str,
+        remote_name: &str,
+        target: &str,
+        auto_rename: bool,
+    ) -> Result<String> {
+        let source_path = self.get_repo_path(source)?;
+        let target_path = self.get_repo_path(target)?;
+
+        if source_path == target_path {
+            anyhow::bail!("A repository cannot be its own remote");
+        }
+
+        RemoteManager::add_local_remote(Some(&source_path), remote_name, &target_path, auto_rename)
+    }
+
+    /// Create a bare repository that borrows the source's objects via git alternates
+    /// Refs (and HEAD) are copied, objects are not, so the fork breaks if the source is deleted
+    pub fn fork_repo(&self, source: &str, dest: &str) -> Result<PathBuf> {
+        let source_path = self.get_repo_path(source)?;
+        let source_repo = Repository::open(&source_path)
+            .context("Failed to open source repository")?;
+
+        let source_objects = git_dir(&source_path)
+            .join("objects")
+            .canonicalize()
+            .context("Failed to resolve source object store")?;
+
+        let dest_path = self.create_repo(dest)?;
+
+        // Don't leave a half-created fork behind to block a retry
+        if let Err(e) = Self::link_fork(&source_repo, &source_objects, &dest_path) {
+            let _ = fs::remove_dir_all(&dest_path);
+            self.remove_empty_namespace(&dest_path);
+            return Err(e);
+        }
+
+        Ok(dest_path)
+    }
+
+    /// Point a freshly created repository at the source's object store and copy its refs and HEAD
+    fn link_fork(source_repo: &Repository, source_objects: &Path, dest_path: &Path) -> Result<()> {
+        // Point the new object store at the source's before opening it
+        fs::write(
+            dest_path.join("objects").join("info").join("alternates"),
+            format!("{}\n", source_objects.display()),
+        )
+        .context("Failed to write alternates file")?;
+
+        let dest_repo = Repository::open(dest_path)
+            .context("Failed to open forked repository")?;
+
+        for reference in source_repo.references().context("Failed to list refs")? {
+            let reference = reference?;
+            let (Some(refname), Some(target)) = (reference.name(), reference.target()) else {
+                continue;
+            };
+            dest_repo.reference(refname, target, true, "fork")
+                .with_context(|| format!("Failed to copy ref '{}'", refname))?;
+        }
+
+        if let Ok(head) = source_repo.find_reference("HEAD")
+            && let Some(target) = head.symbolic_target()
+        {
+            dest_repo.set_head(target)
+                .context("Failed to set HEAD")?;
+        }
+
+        Ok(())
+    }
+
+    /// Find hub repositories that borrow objects from `name` through alternates
+    /// Deleting or moving `name` would break them
+    pub fn find_forks_of(&self, name: &str) -> Result<Vec<String>> {
+        let Ok(objects) = git_dir(&self.get_repo_path(name)?).join("objects").canonicalize() else {
+            return Ok(Vec::new());
+        };
+
+        let mut forks = Vec::new();
+        for fork in self.list_repos()? {
+            let fork_path = self.get_repo_path(&fork)?;
+            let borrows = read_alternates(&git_dir(&fork_path))
+                .iter()
+                .filter_map(|alternate| alternate.canonicalize().ok())
+                .any(|alternate| alternate == objects);
+            if borrows {
+                forks.push(fork);
+            }
+        }
+
+        Ok(forks)
+    }
+
+    /// Copy all objects borrowed through alternates into the repository and drop the alternates
+    /// Afterwards the repository no longer depends on the repository it was forked from
+    pub fn detach_fork(&self, name: &str) -> Result<()> {
+        let repo_path = self.get_repo_path(name)?;
+        let alternates = git_dir(&repo_path).join("objects").join("info").join("alternates");
+
+        if !alternates.exists() {
+            anyhow::bail!("Repository '{}' is not a fork (no alternates file)", name);
+        }
+
+        self.ensure_within_hub(&repo_path)?;
+
+        // Without -l, repack -a also packs objects reachable only through alternates
+        let output = Command::new("git")
+            .arg("--git-dir")
+            .arg(git_dir(&repo_path))
+            .args(["repack", "-a", "-d", "-q"])
+            .output()
+            .context("Failed to run 'git repack' (is git installed?)")?;
+
+        if !output.status.success() {
+            anyhow::bail!("git repack failed: {}", String::from_utf8_lossy(&output.stderr).trim());
+        }
+
+        fs::remove_file(&alternates)
+            .context("Failed to remove alternates file")?;
+
+        Ok(())
+    }
+
+    /// Group repositories that look like copies of each other
+    /// Repositories match when HEAD and every ref point at the same commits; empty repositories are ignored
+    /// Forks that already borrow another member's objects through alternates are left out, since they hold
+    /// no copy of their own
+    /// Returns clusters of two or more names, each sorted by name
+    pub fn find_duplicates(&self) -> Result<Vec<Vec<String>>> {
+        let mut clusters: BTreeMap<_, Vec<(String, PathBuf)>> = BTreeMap::new();
+
+        for name in self.list_repos()? {
+            let path = self.get_repo_path(&name)?;
+            let Ok(repo) = Repository::open(&path) else {
+                continue;
+            };
+
+            let mut fingerprint: Vec<(String, git2::Oid)> = repo
+                .references()
+                .context("Failed to list refs")?
+                .flatten()
+                .filter_map(|reference| Some((reference.name()?.to_string(), reference.target()?)))
+                .collect();
+            if fingerprint.is_empty() {
+                continue;
+            }
+
+            if let Ok(head) = repo.head()
+                && let Some(oid) = head.target()
+            {
+                fingerprint.push(("HEAD".to_string(), oid));
+            }
+
+            fingerprint.sort();
+            clusters.entry(fingerprint).or_default().push((name, git_dir(&path)));
+        }
+
+        let mut duplicates: Vec<Vec<String>> = clusters
+            .into_values()
+            .map(|members| {
+                let objects: Vec<PathBuf> = members
+                    .iter()
+                    .filter_map(|(_, dir)| dir.join("objects").canonicalize().ok())
+                    .collect();
+
+                let mut names: Vec<String> = members
+                    .into_iter()
+                    .filter(|(_, dir)| {
+                        !read_alternates(dir)
+                            .iter()
+                            .filter_map(|alternate| alternate.canonicalize().ok())
+                            .any(|alternate| objects.contains(&alternate))
+                    })
+                    .map(|(name, _)| name)
+                    .collect();
+                names.sort();
+                names
+            })
+            .filter(|names| names.len() > 1)
+            .collect();
+        duplicates.sort();
+
+        Ok(duplicates)
+    }
+
+    /// Write the repository's refs (name -> oid) to `<hub>/.deleted-refs/<name>-<timestamp>.json`
+    /// Lets commits be recovered from other repositories that still have the objects
+    pub fn snapshot_refs(&self, name: &str) -> Result<PathBuf> {
+        let repo_path = self.get_repo_path(name)?;
+        let repo = Repository::open(&repo_path)
+            .context("Failed to open repository")?;
+
+        let mut refs = serde_json::Map::new();
+        for reference in repo.references().context("Failed to list refs")? {
+            let reference = reference?;
+            if let (Some(refname), Some(target)) = (reference.name(), reference.target()) {
+                refs.insert(refname.to_string(), target.to_string().into());
+            }
+        }
+
+        let head = repo
+            .find_reference("HEAD")
+            .ok()
+            .and_then(|head| head.symbolic_target().map(str::to_string));
+
+        let now = Local::now();
+        let snapshot = serde_json::json!({
+            "repo": self.display_name(&repo_path),
+            "deleted": now,
+            "head": head,
+            "refs": refs,
+        });
+
+        let snapshot_path = self.hub_path.join(".deleted-refs").join(format!(
+            "{}-{}.json",
+            self.display_name(&repo_path),
+            now.format("%Y%m%d-%H%M%S")
+        ));
+        if let Some(parent) = snapshot_path.parent() {
+            fs::create_dir_all(parent)
+                .context("Failed to create ref snapshot directory")?;
+        }
+
+        fs::write(&snapshot_path, serde_json::to_string_pretty(&snapshot)?)
+            .context("Failed to write ref snapshot")?;
+
+        Ok(snapshot_path)
+    }
+
+    /// Delete repository with safety checks
+    /// With `verify` false, directories that are not valid Git repositories (e.g., partial mirrors)
+    /// are removed too, as long as they are inside the hub, are not hidden hub-internal directories
+    /// (such as `.deleted-refs`), and hold no repository below them (so a namespace is never removed)
+    pub fn delete_repo(&self, name: &str, verify: bool) -> Result<()> {
+        let repo_path = self.resolve_repo_path(name)?;
+
+        if !repo_path.exists() {
+            anyhow::bail!("Repository '{}' does not exist", name);
+        }
+
+        // Additional safety check: verify it's actually a git repository
+        if verify && !self.is_valid_git_repo(&repo_path)? {
+            anyhow::bail!("Path '{}' is not a valid Git repository", repo_path.display());
+        }
+
+        self.ensure_within_hub(&repo_path)?;
+        self.ensure_no_forks(name)?;
+
+        if !verify {
+            let relative = repo_path.strip_prefix(&self.hub_path).unwrap_or(&repo_path);
+            if relative.components().any(|c| c.as_os_str().to_string_lossy().starts_with('.')) {
+                anyhow::bail!("Refusing to delete hub-internal directory '{}'", repo_path.display());
+            }
+
+            if let Some(nested) = self.find_nested_repo(&repo_path) {
+                anyhow::bail!(
+                    "Refusing to delete '{}': it contains the repository '{}'",
+                    repo_path.display(),
+                    nested.display()
+                );
+            }
+        }
+
+        fs::remove_dir_all(&repo_path)
+            .context("Failed to delete repository")?;
+
+        self.remove_empty_namespace(&repo_path);
+
+        Ok(())
+    }
+
+    /// Move repository to another hub
+    /// Renames within the same filesystem, copies and deletes across filesystems
+    /// Refuses to move a repository that forks borrow objects from
+    /// Returns the absolute path of the moved repository
+    ///
+    /// # Arguments
+    /// * `name` - Repository name in this hub
+    /// * `dest` - Destination hub (must already be initialized)
+    pub fn relocate_repo(&self, name: &str, dest: &LocalGitHub) -> Result<PathBuf> {
+        let repo_path = self.get_repo_path(name)?;
+
+        if !dest.hub_path.is_dir() {
+            anyhow::bail!("Destination hub '{}' does not exist", dest.hub_path.display());
+        }
+
+        if self.hub_path.canonicalize()? == dest.hub_path.canonicalize()? {
+            anyhow::bail!("Source and destination hub are the same");
+        }
+
+        if !self.is_valid_git_repo(&repo_path)? {
+            anyhow::bail!("Path '{}' is not a valid Git repository", repo_path.display());
+        }
+
+        self.ensure_within_hub(&repo_path)?;
+        self.ensure_no_forks(name)?;
+
+        // Keep the on-disk layout (suffix, namespace) in the destination hub
+        let relative = self.display_name(&repo_path);
+        let dest_path = dest.hub_path.join(&relative);
+
+        if dest_path.exists() || dest.repo_exists(&relative) {
+            anyhow::bail!("Repository '{}' already exists in destination hub", relative);
+        }
+
+        if let Some(parent) = dest_path.parent() {
+            fs::create_dir_all(parent)
+                .context("Failed to create namespace directory")?;
+        }
+
+        match fs::rename(&repo_path, &dest_path) {
+            Ok(()) => {}
+            Err(e) if e.kind() == io::ErrorKind::CrossesDevices => {
+                copy_dir_all(&repo_path, &dest_path)
+                    .context("Failed to copy repository to destination hub")?;
+                fs::remove_dir_all(&repo_path)
+                    .context("Failed to remove repository from source hub")?;
+            }
+            Err(e) => {
+                return Err(e).context("Failed to move repository");
+            }
+        }
+
+        self.remove_empty_namespace(&repo_path);
+
+        dest_path.canonicalize()
+            .context("Failed to resolve moved repository path")
+    }
+
+    /// Get repository information
+    /// Commits are left as None when `options.count_commits` is false
+    pub fn get_repo_info(&self, name: &str, options: InfoOptions) -> Result<RepoInfo> {
+        let repo_path = self.resolve_repo_path(name)?;
+
+        if !repo_path.exists() {
+            anyhow::bail!("Repository '{}' does not exist", name);
+        }
+
+        // Get repository size
+        let (size, unreadable) = self.get_dir_size(&repo_path, options.size_mode)?;
+
+        // Get modification time
+        let metadata = fs::metadata(&repo_path)?;
+        let modified: DateTime<Local> = metadata.modified()?.into();
+
+        // Open repository once for history and ref queries
+        let open_start = Instant::now();
+        let (repo, error) = match Repository::open(&repo_path) {
+            Ok(repo) => (Some(repo), None),
+            Err(e) => (None, Some(format!("Failed to open repository: {}", e.message()))),
+        };
+        let open = open_start.elapsed();
+
+        // Get commit count
+        let walk_start = Instant::now();
+        let commits = repo
+            .as_ref()
+            .filter(|_| options.count_commits)
+            .and_then(|repo| self.get_commit_count(repo));
+        let walk = walk_start.elapsed();
+
+        let empty = repo.as_ref().is_some_and(is_empty);
+
+        // Get branch and tag counts
+        let (branch_count, tag_count) = repo
+            .as_ref()
+            .map_or((0, 0), |repo| self.get_ref_counts(repo));
+
+        // Get real activity time, which unlike mtime is not touched by gc
+        let last_commit = repo.as_ref().and_then(last_commit_time);
+
+        // Get push activity from ref files, cheaper than walking history
+        let last_ref_update = last_ref_update(&git_dir(&repo_path));
+
+        // Get upstream source of mirrored repositories
+        let origin_url = repo
+            .as_ref()
+            .and_then(|repo| repo.find_remote("origin").ok())
+            .and_then(|remote| remote.url().map(str::to_string));
+
+        // Get object stores this repository depends on
+        let alternates = read_alternates(&git_dir(&repo_path));
+
+        let repo_name = self.display_name(&repo_path);
+        let bare = !repo_path.join(".git").is_dir();
+
+        Ok(RepoInfo {
+            name: repo_name,
+            path: repo_path,
+            size,
+            modified,
+            last_commit,
+            last_ref_update,
+            commits,
+            empty,
+            unreadable,
+            branch_count,
+            tag_count,
+            bare,
+            origin_url,
+            alternates,
+            error,
+            timing: RepoTiming { open, walk },
+        })
+    }
+
+    /// Placeholder information for a repository whose details could not be gathered
+    fn unavailable_repo_info(&self, name: &str, error: anyhow::Error) -> RepoInfo {
+        let path = self.hub_path.join(name);
+        let modified = fs::metadata(&path)
+            .and_then(|metadata| metadata.modified())
+            .map(DateTime::from)
+            .unwrap_or_else(|_| Local::now());
+
+        RepoInfo {
+            name: name.to_string(),
+            bare: !path.join(".git").is_dir(),
+            path,
+            size: 0,
+            modified,
+            last_commit: None,
+            last_ref_update: None,
+            commits: None,
+            empty: false,
+            unreadable: 0,
+            branch_count: 0,
+            tag_count: 0,
+            origin_url: None,
+            alternates: Vec::new(),
+            error: Some(format!("{:#}", error)),
+            timing: RepoTiming::default(),
+        }
+    }
+
+    /// Find bare repositories stored without the .git suffix
+    /// Listings skip these (such directories are treated as namespaces)
+    pub fn find_unsuffixed_repos(&self) -> Result<Vec<String>> {
+        let mut found = Vec::new();
+
+        if !self.hub_path.exists() {
+            return Ok(found);
+        }
+
+        let mut pending = vec![(self.hub_path.clone(), 0)];
+        while let Some((dir, depth)) = pending.pop() {
+            for entry in fs::read_dir(&dir).context("Failed to read hub directory")? {
+                let path = entry?.path();
+                let hidden = path.file_name().is_some_and(|n| n.to_string_lossy().starts_with('.'));
+
+                if !path.is_dir()
+                    || hidden
+                    || path.extension().is_some_and(|e| e == "git")
+                    || path.join(".git").is_dir()
+                {
+                    continue;
+                }
+
+                if self.is_valid_git_repo(&path)? {
+                    found.push(self.display_name(&path));
+                } else if depth < MAX_NAMESPACE_DEPTH {
+                    pending.push((path, depth + 1));
+                }
+            }
+        }
+
+        found.sort();
+        Ok(found)
+    }
+
+    /// Rename a bare repository stored without the .git suffix to `<name>.git`
+    /// Returns the new repository path
+    pub fn adopt_repo(&self, name: &str) -> Result<PathBuf> {
+        // Validate the name, then address the directory exactly as given
+        self.resolve_repo_path(name)?;
+        let repo_path = self.hub_path.join(name);
+
+        if name.ends_with(".git") {
+            anyhow::bail!("Repository '{}' already has the .git suffix", name);
+        }
+
+        if !repo_path.is_dir() {
+            anyhow::bail!("Directory '{}' does not exist in hub", name);
+        }
+
+        if repo_path.join(".git").is_dir() {
+            anyhow::bail!("'{}' is a repository with a working tree, which is stored without the suffix", name);
+        }
+
+        if !self.is_valid_git_repo(&repo_path)? {
+            anyhow::bail!("Path '{}' is not a valid Git repository", repo_path.display());
+        }
+
+        self.ensure_within_hub(&repo_path)?;
+
+        let dest_path = self.hub_path.join(Self::with_git_suffix(name));
+        if dest_path.exists() {
+            anyhow::bail!("Repository '{}' already exists", self.display_name(&dest_path));
+        }
+
+        fs::rename(&repo_path, &dest_path)
+            .context("Failed to rename repository")?;
+
+        Ok(dest_path)
+    }
+
+    /// Get full path of repository
+    pub fn get_repo_path(&self, name: &str) -> Result<PathBuf> {
+        let repo_path = self.resolve_repo_path(name)?;
+
+        if !repo_path.exists() {
+            anyhow::bail!("Repository '{}' does not exist", name);
+        }
+
+        Ok(repo_path)
+    }
+
+    /// Check if repository exists
+    pub fn repo_exists(&self, name: &str) -> bool {
+        self.resolve_repo_path(name)
+            .map(|path| path.exists())
+            .unwrap_or(false)
+    }
+
+    /// Validate repository name
+    /// A name may contain up to `MAX_NAMESPACE_DEPTH` '/' separators (e.g., "team/project")
+    pub fn validate_repo_name(&self, name: &str) -> Result<()> {
+        if name.is_empty() {
+            anyhow::bail!("Repository name cannot be empty");
+        }
+
+        // Check for invalid characters
+        let invalid_chars = ['\\', ':', '*', '?', '"', '<', '>', '|'];
+        for c in invalid_chars {
+            if name.contains(c) {
+                anyhow::bail!("Repository name cannot contain '{}'", c);
+            }
+        }
+
+        // Check namespace depth
+        let components: Vec<&str> = name.split('/').collect();
+        if components.len() > MAX_NAMESPACE_DEPTH + 1 {
+            anyhow::bail!(
+                "Repository name cannot be nested more than {} namespace level(s)",
+                MAX_NAMESPACE_DEPTH
+            );
+        }
+
+        for (i, component) in components.iter().enumerate() {
+            // Catches leading, trailing and doubled slashes (including absolute paths)
+            if component.is_empty() {
+                anyhow::bail!("Repository name cannot contain empty path components");
+            }
+
+            // Check for reserved names
+            if *component == "." || *component == ".." {
+                anyhow::bail!("Repository name cannot be '.' or '..'");
+            }
+
+            // Namespaces must not look like repositories themselves
+            if i + 1 < components.len() && component.ends_with(".git") {
+                anyhow::bail!("Namespace '{}' cannot end with '.git'", component);
+            }
+        }
+
+        // Check .git suffix: "foo" and "foo.git" both map to "foo.git" on disk,
+        // but "foo.git.git" or a bare ".git" would be ambiguous
+        let base = components[components.len() - 1];
+        let stem = base.strip_suffix(".git").unwrap_or(base);
+        if stem.is_empty() {
+            anyhow::bail!("Repository name cannot be just '.git'");
+        }
+        if stem.ends_with(".git") {
+            anyhow::bail!("Repository name cannot end with '.git' more than once");
+        }
+
+        // Check length
+        if name.len() > 255 {
+            anyhow::bail!("Repository name is too long (max 255 characters)");
+        }
+
+        Ok(())
+    }
+
+    /// Get on-disk repository name used for lookups
+    /// In exact mode the name is used verbatim
+    fn repo_name_for(&self, name: &str) -> String {
+        if self.exact_names {
+            name.to_string()
+        } else {
+            Self::with_git_suffix(name)
+        }
+    }
+
+    /// Append the .git suffix unless the name already ends with it
+    pub fn with_git_suffix(name: &str) -> String {
+        if name.ends_with(".git") {
+            name.to_string()
+        } else {
+            format!("{}.git", name)
+        }
+    }
+
+    /// Resolve repository name to its path inside the hub
+    /// Rejects absolute paths and '.'/'..' components so lookups never escape the hub
+    fn resolve_repo_path(&self, name: &str) -> Result<PathBuf> {
+        let relative = Path::new(name);
+        let is_plain = relative
+            .components()
+            .all(|c| matches!(c, Component::Normal(_)));
+
+        if name.is_empty() || !is_plain {
+            anyhow::bail!("Invalid repository name '{}'", name);
+        }
+
+        let bare_path = self.hub_path.join(self.repo_name_for(name));
+
+        // Fall back to a repository with a working tree stored without the suffix
+        if !bare_path.exists() {
+            let worktree_path = self.hub_path.join(name);
+            if worktree_path.join(".git").is_dir() {
+                return Ok(worktree_path);
+            }
+        }
+
+        Ok(bare_path)
+    }
+
+    /// Verify that `path` resolves to a descendant of the hub root
+    /// Both sides are canonicalized, so `..` components and symlinks pointing out of the hub are caught
+    /// even when a name bypassed `validate_repo_name`
+    pub fn ensure_within_hub(&self, path: &Path) -> Result<()> {
+        let hub = self.hub_path.canonicalize()
+            .context("Failed to resolve hub path")?;
+        let target = path.canonicalize()
+            .with_context(|| format!("Failed to resolve path '{}'", path.display()))?;
+
+        if target == hub || !target.starts_with(&hub) {
+            anyhow::bail!("Path '{}' is not inside the hub '{}'", target.display(), hub.display());
+        }
+
+        Ok(())
+    }
+
+    /// Get repository name relative to hub root (e.g., "team/project.git")
+    fn display_name(&self, repo_path: &Path) -> String {
+        repo_path
+            .strip_prefix(&self.hub_path)
+            .unwrap_or(repo_path)
+            .to_string_lossy()
+            .to_string()
+    }
+
+    /// Remove namespace directory of a removed repository if it is now empty
+    fn remove_empty_namespace(&self, repo_path: &Path) {
+        if let Some(parent) = repo_path.parent()
+            && parent != self.hub_path
+        {
+            let _ = fs::remove_dir(parent);
+        }
+    }
+
+    /// Initialize a new repository in the hub, bare or with a working tree
+    fn init_repo(&self, name: &str, bare: bool) -> Result<PathBuf> {
+        // Validate repository name
+        self.validate_repo_name(name)?;
+
+        // New bare repositories always get exactly one .git suffix, even in exact mode
+        self.resolve_repo_path(name)?;
+        let repo_path = if bare {
+            self.hub_path.join(Self::with_git_suffix(name))
+        } else {
+            self.hub_path.join(name)
+        };
+
+        if repo_path.exists() || self.repo_exists(name) {
+            anyhow::bail!("Repository '{}' already exists", name);
+        }
+
+        // Create namespace directories if needed
+        if let Some(parent) = repo_path.parent() {
+            if parent.join(".git").exists() {
+                anyhow::bail!("Namespace '{}' is an existing repository", self.display_name(parent));
+            }
+            fs::create_dir_all(parent)
+                .context("Failed to create namespace directory")?;
+        }
+
+        let mut opts = RepositoryInitOptions::new();
+        opts.bare(bare);
+        opts.no_reinit(true);
+
+        let repo = Repository::init_opts(&repo_path, &opts)
+            .context(if bare {
+                "Failed to initialize bare repository"
+            } else {
+                "Failed to initialize repository"
+            })?;
+
+        if let Some(shared) = self.shared {
+            share_repo(&repo, shared)?;
+        }
+
+        Ok(repo_path)
+    }
+
+    /// Calculate directory size
+    /// Walks the tree with an explicit stack and does not follow symlinks,
+    /// so deeply nested directories or symlink loops cannot overflow the stack
+    /// Entries that cannot be read for lack of permission (e.g., owned by another user on a shared hub)
+    /// are skipped and counted; returns the total size and the number of skipped entries
+    fn get_dir_size(&self, path: &Path, size_mode: SizeMode) -> Result<(u64, usize)> {
+        let mut total = 0;
+        let mut skipped = 0;
+
+        if !path.is_dir() {
+            return Ok((total, skipped));
+        }
+
+        let mut pending = vec![path.to_path_buf()];
+
+        while let Some(dir) = pending.pop() {
+            let entries = match fs::read_dir(&dir) {
+                Ok(entries) => entries,
+                Err(e) if e.kind() == io::ErrorKind::PermissionDenied => {
+                    skipped += 1;
+                    continue;
+                }
+                Err(e) => return Err(e).context("Failed to read directory"),
+            };
+
+            for entry in entries {
+                let entry = entry?;
+
+                if entry.file_type()?.is_dir() {
+                    pending.push(entry.path());
+                    continue;
+                }
+
+                match entry.metadata() {
+                    Ok(metadata) => total += Self::file_size(&metadata, size_mode),
+                    Err(e) if e.kind() == io::ErrorKind::PermissionDenied => skipped += 1,
+                    Err(e) => return Err(e.into()),
+                }
+            }
+        }
+
+        Ok((total, skipped))
+    }
+
+    /// Get size of a single file according to size mode
+    fn file_size(metadata: &fs::Metadata, size_mode: SizeMode) -> u64 {
+        match size_mode {
+            SizeMode::Apparent => metadata.len(),
+            #[cfg(unix)]
+            SizeMode::Disk => {
+                use std::os::unix::fs::MetadataExt;
+                metadata.blocks() * 512
+            }
+            // Allocated size is not available, fall back to logical size
+            #[cfg(not(unix))]
+            SizeMode::Disk => metadata.len(),
+        }
+    }
+
+    /// Check if path is a valid Git repository
+    fn is_valid_git_repo(&self, path: &Path) -> Result<bool> {
+        let path = git_dir(path);
+
+        let head_path = path.join("HEAD");
+        let objects_path = path.join("objects");
+        let refs_path = path.join("refs");
+
+        Ok(head_path.exists() && objects_path.exists() && refs_path.exists())
+    }
+
+    /// Fail when other hub repositories borrow objects from `name`
+    fn ensure_no_forks(&self, name: &str) -> Result<()> {
+        let forks = self.find_forks_of(name)?;
+        if !forks.is_empty() {
+            anyhow::bail!(
+                "Repository '{}' is the source of forks that borrow its objects: {} (detach them with 'fork --detach <fork>' or delete them first)",
+                name,
+                forks.join(", ")
+            );
+        }
+
+        Ok(())
+    }
+
+    /// Find a repository anywhere below `path` (not `path` itself), without following symlinks
+    fn find_nested_repo(&self, path: &Path) -> Option<PathBuf> {
+        WalkDir::new(path)
+            .min_depth(1)
+            .into_iter()
+            .filter_map(|entry| entry.ok())
+            .filter(|entry| entry.file_type().is_dir())
+            .map(|entry| entry.into_path())
+            .find(|dir| {
+                dir.file_name().is_some_and(|name| name == ".git")
+                    || self.is_valid_git_repo(dir).unwrap_or(false)
+            })
+    }
+
+    /// Get commit count from repository
+    /// Returns None if HEAD cannot be resolved or the history cannot be walked
+    fn get_commit_count(&self, repo: &Repository) -> Option<usize> {
+        let head = repo.revparse_single("HEAD").ok()?;
+        let commit = head.as_commit()?;
+
+        count_commits(repo, commit.id())
+    }
+
+    /// Get branch and tag counts from repository
+    fn get_ref_counts(&self, repo: &Repository) -> (usize, usize) {
+        let branch_count = repo
+            .branches(None)
+            .map(|branches| branches.count())
+            .unwrap_or(0);
+        let tag_count = repo
+            .tag_names(None)
+            .map(|tags| tags.len())
+            .unwrap_or(0);
+
+        (branch_count, tag_count)
+    }
+}
+
+/// Get git directory of a hub repository
+/// Repositories with a working tree keep their git directory in `.git`
+fn git_dir(path: &Path) -> PathBuf {
+    let inner = path.join(".git");
+    if inner.is_dir() { inner } else { path.to_path_buf() }
+}
+
+/// Remote callbacks that log transfer details to stderr
+fn verbose_callbacks() -> git2::RemoteCallbacks<'static> {
+    let mut callbacks = git2::RemoteCallbacks::new();
+
+    callbacks.sideband_progress(|data| {
+        eprint!("remote: {}", String::from_utf8_lossy(data));
+        true
+    });
+    callbacks.transfer_progress(|progress| {
+        eprintln!(
+            "transfer: {}/{} objects received, {}/{} indexed, {} bytes",
+            progress.received_objects(),
+            progress.total_objects(),
+            progress.indexed_objects(),
+            progress.total_objects(),
+            progress.received_bytes()
+        );
+        true
+    });
+    callbacks.update_tips(|refname, old, new| {
+        eprintln!("update: {} {}..{}", refname, old, new);
+        true
+    });
+
+    callbacks
+}
+
+/// Collect commits, trees and blobs reachable from `tip` (excluding history of `hide`) that are not in `known`
+fn collect_objects(
+    repo: &Repository,
+    tip: git2::Oid,
+    hide: Option<git2::Oid>,
+    known: &HashSet<git2::Oid>,
+) -> Result<HashSet<git2::Oid>> {
+    let mut found = HashSet::new();
+
+    let mut revwalk = repo.revwalk().context("Failed to walk history")?;
+    revwalk.push(tip)?;
+    if let Some(hide) = hide {
+        revwalk.hide(hide)?;
+    }
+
+    let mut pending_trees = Vec::new();
+    for oid in revwalk {
+        let commit = repo.find_commit(oid?)?;
+        if !known.contains(&commit.id()) {
+            found.insert(commit.id());
+        }
+        pending_trees.push(commit.tree_id());
+
+        // Trees shared with already-seen commits are skipped as a whole
+        while let Some(tree_oid) = pending_trees.pop() {
+            if known.contains(&tree_oid) || !found.insert(tree_oid) {
+                continue;
+            }
+
+            for entry in repo.find_tree(tree_oid)?.iter() {
+                match entry.kind() {
+                    Some(ObjectType::Tree) => pending_trees.push(entry.id()),
+                    Some(ObjectType::Blob) if !known.contains(&entry.id()) => {
+                        found.insert(entry.id());
+                    }
+                    _ => {}
+                }
+            }
+        }
+    }
+
+    Ok(found)
+}
+
+/// Latest author or committer time across all local branch tips
+fn last_commit_time(repo: &Repository) -> Option<DateTime<Local>> {
+    repo.branches(Some(git2::BranchType::Local))
+        .ok()?
+        .flatten()
+        .filter_map(|(branch, _)| branch.get().peel_to_commit().ok())
+        .map(|commit| commit.author().when().seconds().max(commit.committer().when().seconds()))
+        .max()
+        .and_then(|seconds| DateTime::from_timestamp(seconds, 0))
+        .map(|time| time.with_timezone(&Local))
+}
+
+/// Newest modification time among files under `refs/` and the `packed-refs` file
+/// Repositories whose refs are all packed only have `packed-refs` to go by
+fn last_ref_update(git_dir: &Path) -> Option<DateTime<Local>> {
+    WalkDir::new(git_dir.join("refs"))
+        .into_iter()
+        .flatten()
+        .filter(|entry| entry.file_type().is_file())
+        .filter_map(|entry| entry.metadata().ok())
+        .chain(fs::metadata(git_dir.join("packed-refs")))
+        .filter_map(|metadata| metadata.modified().ok())
+        .max()
+        .map(DateTime::from)
+}
+
+/// Set `core.sharedRepository` and widen permissions of the git directory like `git init --shared`
+/// libgit2's own shared modes leave `objects/` private and make `all` world-writable, so permissions are set here
+fn share_repo(repo: &Repository, shared: SharedMode) -> Result<()> {
+    let value = match shared {
+        SharedMode::Umask => "umask",
+        SharedMode::Group => "group",
+        SharedMode::All => "all",
+    };
+    repo.config()
+        .and_then(|mut config| config.set_str("core.sharedRepository", value))
+        .context("Failed to set core.sharedRepository")?;
+
+    #[cfg(unix)]
+    if shared != SharedMode::Umask {
+        use std::os::unix::fs::PermissionsExt;
+
+        // Group read/write (plus setgid on directories so new entries keep the group), and read for others with `all`
+        let (dir_bits, file_bits) = match shared {
+            SharedMode::All => (0o2775, 0o664),
+            _ => (0o2770, 0o660),
+        };
+
+        for entry in WalkDir::new(repo.path()) {
+            let entry = entry.context("Failed to read repository directory")?;
+            let bits = if entry.file_type().is_dir() { dir_bits } else { file_bits };
+            let mode = entry.metadata()?.permissions().mode() | bits;
+            fs::set_permissions(entry.path(), fs::Permissions::from_mode(mode))
+                .with_context(|| format!("Failed to set permissions of '{}'", entry.path().display()))?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Object directories listed in `objects/info/alternates`, with relative entries resolved against `objects/`
+fn read_alternates(git_dir: &Path) -> Vec<PathBuf> {
+    let objects = git_dir.join("objects");
+
+    fs::read_to_string(objects.join("info").join("alternates"))
+        .map(|content| {
+            content
+                .lines()
+                .map(str::trim)
+                .filter(|line| !line.is_empty() && !line.starts_with('#'))
+                .map(|line| objects.join(line))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Check if a repository has no refs yet, like a freshly created one with an unborn HEAD
+fn is_empty(repo: &Repository) -> bool {
+    repo.is_empty().unwrap_or(false)
+}
+
+/// Count commits reachable from `oid`
+fn count_commits(repo: &Repository, oid: git2::Oid) -> Option<usize> {
+    let mut revwalk = repo.revwalk().ok()?;
+    revwalk.push(oid).ok()?;
+    Some(revwalk.count())
+}
+
+/// Lazy iterator over repository names in a hub
+/// Descends into namespace directories up to `MAX_NAMESPACE_DEPTH`
+pub struct RepoNames {
+    /// Open directory readers with their name prefix and namespace depth
+    stack: Vec<(fs::ReadDir, String, usize)>,
+    /// Error opening the hub root, reported on the first call to `next`
+    root_error: Option<anyhow::Error>,
+}
+
+impl RepoNames {
+    fn new(hub_path: &Path) -> Self {
+        let mut names = Self {
+            stack: Vec::new(),
+            root_error: None,
+        };
+
+        // A missing hub simply has no repositories
+        if hub_path.exists() {
+            match fs::read_dir(hub_path) {
+                Ok(entries) => names.stack.push((entries, String::new(), 0)),
+                Err(e) => names.root_error = Some(anyhow::Error::new(e).context("Failed to read hub directory")),
+            }
+        }
+
+        names
+    }
+}
+
+impl Iterator for RepoNames {
+    type Item = Result<String>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(e) = self.root_error.take() {
+            return Some(Err(e));
+        }
+
+        loop {
+            let (entries, prefix, depth) = self.stack.last_mut()?;
+
+            let entry = match entries.next() {
+                Some(Ok(entry)) => entry,
+                Some(Err(e)) if prefix.is_empty() => return Some(Err(e.into())),
+                Some(Err(e)) => {
+                    let name = prefix.trim_end_matches('/').to_string();
+                    self.stack.pop();
+                    return Some(Err(UnreadableNamespace { name, source: e }.into()));
+                }
+                None => {
+                    self.stack.pop();
+                    continue;
+                }
+            };
+
+            let path = entry.path();
+            if !path.is_dir() {
+                continue;
+            }
+
+            let name = entry.file_name().to_string_lossy().to_string();
+
+            if path.extension().is_some_and(|e| e == "git") || path.join(".git").is_dir() {
+                return Some(Ok(format!("{}{}", prefix, name)));
+            }
+
+            if *depth < MAX_NAMESPACE_DEPTH && !name.starts_with('.') {
+                // Treat other directories as namespaces
+                let prefix = format!("{}{}/", prefix, name);
+                let depth = *depth + 1;
+
+                match fs::read_dir(&path) {
+                    Ok(entries) => self.stack.push((entries, prefix, depth)),
+                    Err(e) => {
+                        let name = prefix.trim_end_matches('/').to_string();
+                        return Some(Err(UnreadableNamespace { name, source: e }.into()));
+                    }
                 }
             }
-            Err(_) => None,
         }
     }
 }
+
+/// A namespace directory whose contents could not be read
+/// Yielded by `RepoNames` so callers can report it and keep listing the rest of the hub
+#[derive(Debug)]
+pub struct UnreadableNamespace {
+    /// Namespace path relative to the hub, e.g. "team"
+    pub name: String,
+    source: io::Error,
+}
+
+impl std::fmt::Display for UnreadableNamespace {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Failed to read namespace '{}'", self.name)
+    }
+}
+
+impl std::error::Error for UnreadableNamespace {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(&self.source)
+    }
+}
+
+/// Recursively copy directory contents, preserving symlinks
+fn copy_dir_all(src: &Path, dest: &Path) -> Result<()> {
+    let mut pending = vec![(src.to_path_buf(), dest.to_path_buf())];
+
+    while let Some((from, to)) = pending.pop() {
+        fs::create_dir_all(&to)?;
+
+        for entry in fs::read_dir(&from)? {
+            let entry = entry?;
+            let file_type = entry.file_type()?;
+            let target = to.join(entry.file_name());
+
+            if file_type.is_dir() {
+                pending.push((entry.path(), target));
+            } else if file_type.is_symlink() {
+                #[cfg(unix)]
+                std::os::unix::fs::symlink(fs::read_link(entry.path())?, &target)?;
+                #[cfg(not(unix))]
+                fs::copy(entry.path(), &target)?;
+            } else {
+                fs::copy(entry.path(), &target)?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Hub in a temporary directory, kept alive by the returned guard
+    fn temp_hub() -> (tempfile::TempDir, LocalGitHub) {
+        let dir = tempfile::tempdir().unwrap();
+        let hub = LocalGitHub::new(dir.path());
+        (dir, hub)
+    }
+
+    /// Append `count` empty commits to HEAD of a repository
+    fn add_commits(repo: &Repository, count: usize) {
+        let signature = git2::Signature::now("Test", "test@example.com").unwrap();
+        let tree_id = repo.treebuilder(None).unwrap().write().unwrap();
+        let tree = repo.find_tree(tree_id).unwrap();
+
+        for i in 0..count {
+            let parent = repo.head().ok().and_then(|head| head.peel_to_commit().ok());
+            let parents: Vec<&git2::Commit> = parent.iter().collect();
+            repo.commit(Some("HEAD"), &signature, &signature, &format!("commit {}", i), &tree, &parents)
+                .unwrap();
+        }
+    }
+
+    #[test]
+    fn commit_count_of_empty_repo_is_none() {
+        let (_dir, hub) = temp_hub();
+        let repo = Repository::open(hub.create_repo("empty").unwrap()).unwrap();
+
+        assert_eq!(hub.get_commit_count(&repo), None);
+    }
+
+    #[test]
+    fn commit_count_matches_history() {
+        let (_dir, hub) = temp_hub();
+        let repo = Repository::open(hub.create_repo("three").unwrap()).unwrap();
+        add_commits(&repo, 3);
+
+        assert_eq!(hub.get_commit_count(&repo), Some(3));
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn dir_size_survives_deep_tree_and_symlink_loop() {
+        let (dir, hub) = temp_hub();
+        let root = dir.path().join("deep.git");
+
+        // Deep enough to matter for a recursive walk, short enough to stay under PATH_MAX
+        let mut deepest = root.clone();
+        for _ in 0..1500 {
+            deepest.push("d");
+        }
+        fs::create_dir_all(&deepest).unwrap();
+        fs::write(deepest.join("file"), b"12345").unwrap();
+        fs::write(root.join("top"), b"abc").unwrap();
+
+        // A directory symlink back to the root would loop forever if followed
+        std::os::unix::fs::symlink(&root, root.join("d").join("loop")).unwrap();
+
+        let (size, skipped) = hub.get_dir_size(&root, SizeMode::Apparent).unwrap();
+        let link_size = fs::symlink_metadata(root.join("d").join("loop")).unwrap().len();
+
+        assert_eq!(size, 5 + 3 + link_size);
+        assert_eq!(skipped, 0);
+    }
+
+    #[test]
+    fn git_suffix_is_added_once() {
+        let (dir, hub) = temp_hub();
+
+        assert!(hub.validate_repo_name("foo").is_ok());
+        assert!(hub.validate_repo_name("foo.git").is_ok());
+        assert!(hub.validate_repo_name("foo.git.git").is_err());
+
+        assert_eq!(hub.repo_name_for("foo"), "foo.git");
+        assert_eq!(hub.repo_name_for("foo.git"), "foo.git");
+
+        // Both spellings create and find the same repository
+        let path = hub.create_repo("foo").unwrap();
+        assert_eq!(path, dir.path().join("foo.git"));
+        assert!(hub.repo_exists("foo.git"));
+        assert!(hub.create_repo("foo.git").is_err());
+    }
+
+    #[test]
+    fn exact_mode_uses_names_verbatim() {
+        let (dir, hub) = temp_hub();
+        let hub = hub.exact_names(true);
+
+        assert_eq!(hub.repo_name_for("foo"), "foo");
+        assert_eq!(hub.repo_name_for("foo.git"), "foo.git");
+        assert!(hub.validate_repo_name("foo.git.git").is_err());
+
+        // New bare repositories still get exactly one suffix, but lookups don't add it
+        let path = hub.create_repo("foo").unwrap();
+        assert_eq!(path, dir.path().join("foo.git"));
+        assert!(!hub.repo_exists("foo"));
+        assert!(hub.repo_exists("foo.git"));
+    }
+
+    /// Assert that validation fails with a message containing `reason`
+    fn assert_rejected(hub: &LocalGitHub, name: &str, reason: &str) {
+        let error = hub.validate_repo_name(name).unwrap_err().to_string();
+        assert!(error.contains(reason), "'{}' rejected with '{}', expected '{}'", name, error, reason);
+    }
+
+    #[test]
+    fn validate_repo_name_rejections() {
+        let (_dir, hub) = temp_hub();
+
+        assert_rejected(&hub, "", "cannot be empty");
+        assert_rejected(&hub, "foo:bar", "cannot contain ':'");
+        assert_rejected(&hub, "..", "cannot be '.' or '..'");
+        assert_rejected(&hub, "team/.", "cannot be '.' or '..'");
+        assert_rejected(&hub, &"a".repeat(256), "too long");
+        assert_rejected(&hub, "a/b/c", "namespace level");
+        assert_rejected(&hub, "team/", "empty path components");
+        assert_rejected(&hub, "/abs", "empty path components");
+        assert_rejected(&hub, "team.git/project", "cannot end with '.git'");
+        assert_rejected(&hub, ".git", "just '.git'");
+
+        assert!(hub.validate_repo_name("team/project").is_ok());
+        assert!(hub.validate_repo_name(&"a".repeat(255)).is_ok());
+    }
+
+    #[test]
+    fn unverified_delete_keeps_namespaces_and_hidden_dirs() {
+        let (dir, hub) = temp_hub();
+        hub.create_repo("team/bar").unwrap();
+        fs::create_dir_all(dir.path().join(".deleted-refs/bar.git")).unwrap();
+        let exact = LocalGitHub::new(dir.path()).exact_names(true);
+
+        let error = exact.delete_repo("team", false).unwrap_err();
+        assert!(error.to_string().contains("contains the repository"), "{}", error);
+        assert!(dir.path().join("team/bar.git").is_dir());
+
+        let error = exact.delete_repo(".deleted-refs", false).unwrap_err();
+        assert!(error.to_string().contains("hub-internal"), "{}", error);
+        assert!(dir.path().join(".deleted-refs").is_dir());
+    }
+
+    #[test]
+    fn unverified_delete_removes_partial_mirror() {
+        let (dir, hub) = temp_hub();
+        let partial = dir.path().join("partial.git");
+        fs::create_dir_all(partial.join("objects/pack")).unwrap();
+
+        assert!(hub.delete_repo("partial", true).is_err());
+        hub.delete_repo("partial", false).unwrap();
+        assert!(!partial.exists());
+    }
+
+    #[test]
+    fn ensure_within_hub_rejects_escapes() {
+        let (dir, hub) = temp_hub();
+        let repo_path = hub.create_repo("inside").unwrap();
+        let outside = tempfile::tempdir().unwrap();
+
+        assert!(hub.ensure_within_hub(&repo_path).is_ok());
+        assert!(hub.ensure_within_hub(dir.path()).is_err());
+        assert!(hub.ensure_within_hub(&repo_path.join("..")).is_err());
+        assert!(hub.ensure_within_hub(&dir.path().join("inside.git/../..")).is_err());
+        assert!(hub.ensure_within_hub(&repo_path.join("../../").join(outside.path().file_name().unwrap())).is_err());
+        assert!(hub.ensure_within_hub(outside.path()).is_err());
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn ensure_within_hub_rejects_symlinks_out_of_hub() {
+        let (dir, hub) = temp_hub();
+        let outside = tempfile::tempdir().unwrap();
+        let link = dir.path().join("escape.git");
+        std::os::unix::fs::symlink(outside.path(), &link).unwrap();
+        std::os::unix::fs::symlink(dir.path(), dir.path().join("root.git")).unwrap();
+
+        assert!(hub.ensure_within_hub(&link).is_err());
+        assert!(hub.ensure_within_hub(&dir.path().join("root.git")).is_err());
+        assert!(hub.delete_repo("escape", false).is_err());
+        assert!(outside.path().exists());
+    }
+
+    #[test]
+    fn forks_are_not_duplicates() {
+        let (_dir, hub) = temp_hub();
+        let source = Repository::open(hub.create_repo("source").unwrap()).unwrap();
+        add_commits(&source, 2);
+        hub.fork_repo("source", "fork").unwrap();
+
+        assert!(hub.find_duplicates().unwrap().is_empty());
+    }
+
+    #[test]
+    fn rename_ref_keeps_namespace_of_short_name() {
+        let (_dir, hub) = temp_hub();
+        let repo = Repository::open(hub.create_repo("refs").unwrap()).unwrap();
+        add_commits(&repo, 1);
+        let head = repo.head().unwrap().name().unwrap().to_string();
+
+        let (old, new) = hub.rename_ref("refs", &head, "main").unwrap();
+        assert_eq!((old.as_str(), new.as_str()), (head.as_str(), "refs/heads/main"));
+
+        let (old, new) = hub.rename_ref("refs", "main", "trunk").unwrap();
+        assert_eq!((old.as_str(), new.as_str()), ("refs/heads/main", "refs/heads/trunk"));
+        assert_eq!(repo.head().unwrap().name(), Some("refs/heads/trunk"));
+    }
+
+    #[test]
+    fn rename_ref_keeps_namespace_of_full_name() {
+        let (_dir, hub) = temp_hub();
+        let repo = Repository::open(hub.create_repo("refs").unwrap()).unwrap();
+        add_commits(&repo, 1);
+        let commit = repo.head().unwrap().peel_to_commit().unwrap();
+        repo.tag_lightweight("v1", commit.as_object(), false).unwrap();
+
+        let (old, new) = hub.rename_ref("refs", "refs/tags/v1", "v2").unwrap();
+        assert_eq!((old.as_str(), new.as_str()), ("refs/tags/v1", "refs/tags/v2"));
+        assert!(repo.find_reference("refs/tags/v2").is_ok());
+        assert!(repo.find_reference("refs/heads/v2").is_err());
+    }
+
+    #[test]
+    fn delete_refuses_fork_source() {
+        let (_dir, hub) = temp_hub();
+        let source = Repository::open(hub.create_repo("source").unwrap()).unwrap();
+        add_commits(&source, 1);
+        hub.fork_repo("source", "fork").unwrap();
+
+        assert_eq!(hub.find_forks_of("source").unwrap(), vec!["fork.git".to_string()]);
+        let error = hub.delete_repo("source", true).unwrap_err();
+        assert!(error.to_string().contains("fork.git"), "{}", error);
+        assert!(hub.repo_exists("source"));
+
+        hub.detach_fork("fork").unwrap();
+        hub.delete_repo("source", true).unwrap();
+    }
+
+    #[test]
+    fn failed_fork_is_cleaned_up() {
+        let (dir, hub) = temp_hub();
+        let source = Repository::open(hub.create_repo("source").unwrap()).unwrap();
+        add_commits(&source, 1);
+        // A ref to a missing object makes copying the refs fail after the fork was created
+        fs::write(dir.path().join("source.git/refs/heads/bad"), format!("{}\n", "1".repeat(40))).unwrap();
+
+        let error = hub.fork_repo("source", "team/fork").unwrap_err();
+        assert!(error.to_string().contains("Failed to copy ref"), "{}", error);
+        assert!(!dir.path().join("team").exists());
+    }
+
+    #[test]
+    fn relocate_refuses_fork_source_and_returns_absolute_path() {
+        let (_dir, hub) = temp_hub();
+        let (_dest_dir, dest) = temp_hub();
+        let source = Repository::open(hub.create_repo("source").unwrap()).unwrap();
+        add_commits(&source, 1);
+        hub.fork_repo("source", "fork").unwrap();
+
+        let error = hub.relocate_repo("source", &dest).unwrap_err();
+        assert!(error.to_string().contains("fork.git"), "{}", error);
+        assert!(hub.repo_exists("source"));
+
+        let moved = hub.relocate_repo("fork", &dest).unwrap();
+        assert!(moved.is_absolute());
+        assert_eq!(moved, dest.hub_path.canonicalize().unwrap().join("fork.git"));
+    }
+}
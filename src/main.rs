@@ -3,27 +3,204 @@ mod remote;
 
 use anyhow::Result;
 use chrono::{DateTime, Local};
-use clap::{Parser, Subcommand};
+use clap::{Parser, Subcommand, ValueEnum};
 use colored::*;
-use dialoguer::{theme::ColorfulTheme, Confirm};
-use hub::LocalGitHub;
+use dialoguer::{theme::ColorfulTheme, Confirm, MultiSelect};
+use hub::{InfoOptions, LocalGitHub, RepoInfo, SharedMode, SizeMode, UnreadableNamespace, INDEX_FILE};
 use humansize::format_size;
-use remote::RemoteManager;
+use remote::{RemoteManager, UrlResolution};
+use std::collections::{BTreeMap, HashSet};
+use std::io::IsTerminal;
 use std::path::PathBuf;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Instant;
 
 /// Local Git - Local Git repository management center
 #[derive(Parser)]
 #[command(name = "local-git-rs")]
 #[command(about = "Manage local Git bare repositories as local backup hub", long_about = None)]
 struct Cli {
-    /// Hub root directory path (default: ~/.local-git-hub)
-    #[arg(short, long, global = true)]
+    /// Hub root directory path (default: $LOCAL_GIT_RS_HUB, then ~/.local-git-hub)
+    #[arg(long, global = true)]
     hub_path: Option<PathBuf>,
 
+    /// When to use colors (default: $LOCAL_GIT_RS_COLOR, then auto)
+    #[arg(long, value_enum, global = true)]
+    color: Option<ColorChoice>,
+
+    /// Answer yes to all confirmation prompts (also $LOCAL_GIT_RS_NO_CONFIRM; required when stdin is not a terminal)
+    #[arg(short = 'y', long = "yes", visible_alias = "no-confirm", global = true)]
+    assume_yes: bool,
+
+    /// Print per-repository timing of git operations to stderr (list, info), or throughput (create -)
+    #[arg(long, global = true)]
+    profile: bool,
+
+    /// Log git transfer details (progress, remote messages, ref updates) to stderr
+    #[arg(short, long, global = true)]
+    verbose: bool,
+
+    /// Match repository names exactly instead of appending the .git suffix
+    #[arg(long, global = true)]
+    no_suffix_match: bool,
+
+    /// Report sizes as allocated disk blocks instead of apparent (logical) file sizes.
+    /// Disk usage reflects compression and sparse files but is only available on Unix
+    #[arg(long, global = true, conflicts_with = "apparent")]
+    disk: bool,
+
+    /// Report sizes as the sum of logical file lengths (default, consistent across platforms)
+    #[arg(long, global = true)]
+    apparent: bool,
+
     #[command(subcommand)]
     command: Commands,
 }
 
+/// When to use colors in output
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum ColorChoice {
+    /// Color when writing to a terminal (honors NO_COLOR)
+    Auto,
+    Always,
+    Never,
+}
+
+/// Output format for repository listings
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum ListFormat {
+    /// Human-readable table
+    Table,
+    /// Comma-separated values with a header row
+    Csv,
+}
+
+/// Timestamp that defines a repository's age
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum AgeFrom {
+    /// Latest commit on any branch (real work; unaffected by gc or repacks)
+    Commit,
+    /// Directory modification time (changes on any write, including gc)
+    Mtime,
+}
+
+/// Sharing mode of new repositories, mirroring `git init --shared`
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum Shared {
+    /// Respect the umask (core.sharedRepository=umask)
+    Umask,
+    /// Group-writable (core.sharedRepository=group)
+    Group,
+    /// Group-writable and readable by everyone (core.sharedRepository=all)
+    All,
+}
+
+impl Shared {
+    fn mode(self) -> SharedMode {
+        match self {
+            Shared::Umask => SharedMode::Umask,
+            Shared::Group => SharedMode::Group,
+            Shared::All => SharedMode::All,
+        }
+    }
+}
+
+/// Column of the detailed repository table
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum Column {
+    /// Repository name, marked [worktree] for non-bare repositories
+    Name,
+    Size,
+    Commits,
+    Branches,
+    Tags,
+    /// Last activity as selected by --age-from
+    Modified,
+    /// Newest ref update (last push)
+    Pushed,
+    /// Origin URL, truncated
+    Origin,
+}
+
+impl Column {
+    /// Columns shown when --columns is not given
+    const DEFAULT: [Column; 7] = [
+        Column::Name,
+        Column::Size,
+        Column::Commits,
+        Column::Branches,
+        Column::Tags,
+        Column::Modified,
+        Column::Pushed,
+    ];
+
+    fn header(self, age_from: AgeFrom) -> &'static str {
+        match self {
+            Column::Name => "Name",
+            Column::Size => "Size",
+            Column::Commits => "Commits",
+            Column::Branches => "Branches",
+            Column::Tags => "Tags",
+            Column::Modified if age_from == AgeFrom::Commit => "Last Commit",
+            Column::Modified => "Modified",
+            Column::Pushed => "Last Push",
+            Column::Origin => "Origin",
+        }
+    }
+
+    fn width(self) -> usize {
+        match self {
+            Column::Name => 30,
+            Column::Size => 12,
+            Column::Commits | Column::Branches => 10,
+            Column::Tags => 6,
+            Column::Modified | Column::Pushed => 20,
+            Column::Origin => ORIGIN_WIDTH,
+        }
+    }
+
+    /// Text columns are left-aligned, numbers and dates right-aligned
+    fn left_aligned(self) -> bool {
+        matches!(self, Column::Name | Column::Origin)
+    }
+
+    fn cell(self, repo: &RepoInfo, age_from: AgeFrom, bytes: bool) -> ColoredString {
+        match self {
+            Column::Name => {
+                let name = if repo.bare {
+                    repo.name.clone()
+                } else {
+                    format!("{} [worktree]", repo.name)
+                };
+                if repo.error.is_some() { name.red() } else { name.dimmed() }
+            }
+            Column::Size => display_size(repo.size, bytes).normal(),
+            Column::Commits => repo.commits.map_or("N/A".to_string(), |c| c.to_string()).yellow(),
+            Column::Branches => repo.branch_count.to_string().normal(),
+            Column::Tags => repo.tag_count.to_string().normal(),
+            Column::Modified => match age_from {
+                AgeFrom::Commit => repo.last_commit.map_or("N/A".to_string(), format_datetime),
+                AgeFrom::Mtime => format_datetime(repo.modified),
+            }
+            .dimmed(),
+            Column::Pushed => repo.last_ref_update.map_or("N/A".to_string(), format_datetime).dimmed(),
+            Column::Origin => truncate(repo.origin_url.as_deref().unwrap_or(""), ORIGIN_WIDTH).cyan(),
+        }
+    }
+}
+
+/// Output format for repository information
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum InfoFormat {
+    /// Human-readable text
+    Text,
+    /// TOML document
+    Toml,
+    /// One uncolored line for shell prompts and status bars, e.g. `foo.git 12MB 34 commits (2d ago)`
+    #[value(alias = "oneline")]
+    Short,
+}
+
 #[derive(Subcommand)]
 enum Commands {
     /// Initialize hub directory
@@ -31,6 +208,40 @@ enum Commands {
 
     /// Create new bare repository
     Create {
+        /// Repository name ('-' reads one name per line from stdin)
+        name: String,
+
+        /// Create a regular repository with a working tree instead of a bare one
+        #[arg(long)]
+        with_worktree: bool,
+
+        /// Copy hooks from this existing hub repository into the new one
+        #[arg(long, value_name = "REPO")]
+        from_hooks: Option<String>,
+
+        /// Enable reflogs (core.logAllRefUpdates) so force-pushed branch tips can be recovered
+        #[arg(long)]
+        reflog: bool,
+
+        /// Seed the new repository with the history of a template (hub repository or URL)
+        #[arg(long = "from", visible_alias = "from-template-repo", value_name = "REPO_OR_URL")]
+        template: Option<String>,
+
+        /// Write this text to the repository's description file
+        #[arg(long)]
+        description: Option<String>,
+
+        /// Make the repository writable by other users, like 'git init --shared' (default: group)
+        #[arg(long, value_enum, num_args = 0..=1, require_equals = true, default_missing_value = "group", value_name = "MODE")]
+        shared: Option<Shared>,
+
+        /// Number of repositories to create in parallel when reading names from stdin
+        #[arg(short, long, default_value_t = 1, value_parser = clap::value_parser!(u32).range(1..))]
+        jobs: u32,
+    },
+
+    /// Check a repository name against the naming rules without creating it
+    ValidateName {
         /// Repository name
         name: String,
     },
@@ -40,28 +251,285 @@ enum Commands {
         /// Show detailed information
         #[arg(short, long)]
         detailed: bool,
+
+        /// Output format, csv always includes details (default: $LOCAL_GIT_RS_FORMAT, then table)
+        #[arg(long, value_enum)]
+        format: Option<ListFormat>,
+
+        /// Group repositories by namespace with per-namespace totals
+        #[arg(long)]
+        tree: bool,
+
+        /// Only show repositories at least this large (e.g., 100MB, 1.5GiB)
+        #[arg(long, value_parser = parse_size)]
+        min_size: Option<u64>,
+
+        /// Only show repositories at most this large (e.g., 100MB, 1.5GiB)
+        #[arg(long, value_parser = parse_size)]
+        max_size: Option<u64>,
+
+        /// Skip counting commits for faster detailed listings
+        #[arg(long)]
+        size_only: bool,
+
+        /// Only show repositories nobody has pushed to (e.g., forgotten placeholders)
+        #[arg(long)]
+        empty: bool,
+
+        /// Print sizes as raw byte counts instead of human-readable units
+        #[arg(long)]
+        bytes: bool,
+
+        /// Show repository names without the .git suffix
+        #[arg(long, visible_alias = "strip-suffix")]
+        no_suffix: bool,
+
+        /// Timestamp shown as last activity: latest branch commit, or directory mtime (changes on gc)
+        #[arg(long, value_enum, default_value = "commit")]
+        age_from: AgeFrom,
+
+        /// Read repository names from the index file instead of scanning the hub
+        #[arg(long, conflicts_with_all = ["detailed", "tree", "min_size", "max_size", "empty"])]
+        from_index: bool,
+
+        /// Add a column with each repository's origin URL (with --detailed)
+        #[arg(long, requires = "detailed")]
+        with_origin: bool,
+
+        /// Columns of the detailed table, in order (e.g., name,size,origin)
+        #[arg(long, value_enum, value_delimiter = ',', requires = "detailed")]
+        columns: Option<Vec<Column>>,
+
+        /// Exit with status 1 instead of warning when no repositories are listed (for scripts)
+        #[arg(short, long)]
+        quiet_empty: bool,
     },
 
     /// Search repositories by name pattern
     Search {
         /// Search pattern
+        #[arg(required_unless_present = "author")]
+        pattern: Option<String>,
+
+        /// Only show repositories whose latest commit author matches this pattern (slower)
+        #[arg(short, long)]
+        author: Option<String>,
+
+        /// Also match the pattern against README files at HEAD (slower)
+        #[arg(long, requires = "pattern")]
+        content: bool,
+
+        /// Show repository names without the .git suffix
+        #[arg(long, visible_alias = "strip-suffix")]
+        no_suffix: bool,
+
+        /// Exit with status 1 and print nothing when no repositories match (for scripts)
+        #[arg(short, long)]
+        quiet_empty: bool,
+    },
+
+    /// Search commit messages across hub repositories
+    GrepLog {
+        /// Text to search for (a regular expression with --regex)
+        pattern: String,
+
+        /// Treat pattern as a regular expression
+        #[arg(short = 'E', long)]
+        regex: bool,
+
+        /// Match case-insensitively
+        #[arg(short, long)]
+        ignore_case: bool,
+
+        /// Only search repositories whose name contains this pattern
+        #[arg(short, long)]
+        repo: Option<String>,
+    },
+
+    /// Search file contents of a repository at a given revision
+    Grep {
+        /// Repository name
+        name: String,
+
+        /// Text to search for (a regular expression with --regex)
         pattern: String,
+
+        /// Revision to search (default: HEAD)
+        #[arg(long)]
+        rev: Option<String>,
+
+        /// Treat pattern as a regular expression
+        #[arg(short = 'E', long)]
+        regex: bool,
+
+        /// Match case-insensitively
+        #[arg(short, long)]
+        ignore_case: bool,
     },
 
     /// Show repository information
     Info {
         /// Repository name
         name: String,
+
+        /// Also show the hub repository's own remotes (e.g., where a mirror came from)
+        #[arg(long)]
+        include_remotes: bool,
+
+        /// Also show commit count, last author and date for this branch, tag or revision
+        #[arg(long = "ref", value_name = "REFNAME")]
+        refname: Option<String>,
+
+        /// Output format (--ref, --upstream and --include-remotes need the default text format)
+        #[arg(long, value_enum, conflicts_with_all = ["include_remotes", "refname", "upstream"])]
+        format: Option<InfoFormat>,
+
+        /// Skip counting commits
+        #[arg(long)]
+        size_only: bool,
+
+        /// Show how far each branch is ahead of/behind its fetched origin counterpart
+        #[arg(long)]
+        upstream: bool,
+
+        /// Print the size as a raw byte count instead of human-readable units
+        #[arg(long)]
+        bytes: bool,
+    },
+
+    /// Verify a repository can be restored (clone, fsck, compare HEAD)
+    TestRestore {
+        /// Repository name
+        name: String,
+    },
+
+    /// Show the reflog of a ref (e.g., to recover a branch tip lost to a force-push)
+    Reflog {
+        /// Repository name
+        name: String,
+
+        /// Ref whose reflog to show (default: HEAD)
+        #[arg(default_value = "HEAD")]
+        refname: String,
+    },
+
+    /// Show how much data each branch adds on top of the default branch
+    BranchSizes {
+        /// Repository name
+        name: String,
+    },
+
+    /// Find repositories that appear to be copies of each other (same HEAD and refs)
+    FindDuplicates,
+
+    /// Delete a ref (e.g., refs/pull/1/head pulled in by a mirror) without cloning
+    DeleteRef {
+        /// Repository name
+        name: String,
+
+        /// Ref to delete, full or short name
+        #[arg(value_name = "REF")]
+        refname: String,
+    },
+
+    /// Rename a ref (e.g., a branch) without cloning
+    RenameRef {
+        /// Repository name
+        name: String,
+
+        /// Current ref name, full or short
+        old: String,
+
+        /// New ref name (without refs/, it stays in the same namespace)
+        new: String,
+    },
+
+    /// Pack loose refs into packed-refs (speeds up repositories with many refs)
+    PackRefs {
+        /// Repository name
+        name: String,
+    },
+
+    /// Write commit-graph files to speed up history walks
+    CommitGraph {
+        /// Repository name (default: all repositories)
+        name: Option<String>,
     },
 
     /// Delete repository
     Delete {
         /// Repository name
-        name: String,
+        #[arg(required_unless_present = "select")]
+        name: Option<String>,
 
         /// Skip confirmation prompt
         #[arg(short, long)]
         force: bool,
+
+        /// Pick the repositories to delete from an interactive list
+        #[arg(long, conflicts_with = "name")]
+        select: bool,
+
+        /// Save the repository's refs to <hub>/.deleted-refs before deleting
+        #[arg(long)]
+        keep_refs_backup: bool,
+
+        /// Delete even if the directory is not a valid Git repository (e.g., a partial mirror)
+        #[arg(long)]
+        no_verify: bool,
+    },
+
+    /// Rename a bare repository stored without the .git suffix to the hub convention
+    Adopt {
+        /// Directory name in hub (without .git)
+        name: String,
+    },
+
+    /// Back up the entire hub into a single .tar.gz archive
+    ExportAll {
+        /// Archive file to write (outside the hub)
+        output: PathBuf,
+    },
+
+    /// Restore a hub from an archive written by export-all
+    ImportAll {
+        /// Archive file to read
+        archive: PathBuf,
+
+        /// Directory to restore into, which must be empty (default: hub path)
+        dest: Option<PathBuf>,
+    },
+
+    /// Catalog repositories at any depth below a directory into an index file
+    Index {
+        /// Directory to scan (default: hub path)
+        root: Option<PathBuf>,
+    },
+
+    /// Check the hub for problems, such as repositories hidden from listings
+    Doctor,
+
+    /// Create a repository sharing another's objects (git alternates)
+    Fork {
+        /// Source repository (with --detach: the fork to detach)
+        source: String,
+
+        /// Name of the new repository
+        #[arg(required_unless_present = "detach", conflicts_with = "detach")]
+        dest: Option<String>,
+
+        /// Copy all borrowed objects into an existing fork so it no longer depends on its source
+        #[arg(long)]
+        detach: bool,
+    },
+
+    /// Move repository to another hub
+    Relocate {
+        /// Repository name
+        name: String,
+
+        /// Destination hub root directory
+        to_hub: PathBuf,
     },
 
     /// Add local remote to current repository
@@ -76,6 +544,26 @@ enum Commands {
         /// Working directory path (default: current directory)
         #[arg(short, long)]
         path: Option<PathBuf>,
+
+        /// Pick a free name (e.g., local-hub-2) if the remote name is taken
+        #[arg(long)]
+        auto_rename: bool,
+
+        /// Make the new remote the upstream of the current branch
+        #[arg(long)]
+        set_upstream: bool,
+
+        /// Store the hub path relative to the repository (for directory trees synced across machines)
+        #[arg(long)]
+        relative: bool,
+
+        /// Store this logical URL and map it to the hub path via insteadOf in global git config
+        #[arg(long, value_name = "NAME", conflicts_with = "relative")]
+        url_alias: Option<String>,
+
+        /// Add the remote to this hub repository instead of a working repository (chained backups)
+        #[arg(long, value_name = "REPO", conflicts_with_all = ["path", "set_upstream", "relative", "url_alias"])]
+        on_hub_repo: Option<String>,
     },
 
     /// Add local backup push URL to existing remote
@@ -97,29 +585,81 @@ enum Commands {
         /// Working directory path (default: current directory)
         #[arg(short, long)]
         path: Option<PathBuf>,
+
+        /// Show where local-path URLs resolve and flag dangling ones
+        #[arg(long, visible_alias = "absolute")]
+        resolve: bool,
     },
 
     /// Delete remote
     RemoveRemote {
         /// Remote name
-        remote_name: String,
+        #[arg(required_unless_present = "matching_hub", conflicts_with = "matching_hub")]
+        remote_name: Option<String>,
+
+        /// Remove every remote that points into the hub instead of a single remote
+        #[arg(long)]
+        matching_hub: bool,
 
         /// Working directory path (default: current directory)
         #[arg(short, long)]
         path: Option<PathBuf>,
+
+        /// Remove a remote that does not point into the hub without asking
+        #[arg(short, long)]
+        force: bool,
     },
 }
 
-fn get_hub_path(cli_path: Option<PathBuf>) -> PathBuf {
-    if let Some(path) = cli_path {
-        path
-    } else {
-        // Default to ~/.local-git-hub
-        let home = std::env::var("HOME").unwrap_or_else(|_| ".".to_string());
-        PathBuf::from(home).join(".local-git-hub")
+/// Loose ref count above which `info` suggests running pack-refs
+const LOOSE_REFS_WARNING: usize = 1000;
+
+/// Maximum width of the origin URL column in detailed listings
+const ORIGIN_WIDTH: usize = 40;
+
+/// Settings that fall back to LOCAL_GIT_RS_* environment variables (for CI)
+/// Precedence: command-line flag, then environment variable, then built-in default
+struct Options {
+    hub_path: PathBuf,
+    assume_yes: bool,
+    color: ColorChoice,
+    /// LOCAL_GIT_RS_FORMAT, used by commands whose --format accepts the value
+    format: Option<String>,
+}
+
+fn resolve_options(cli: &Cli) -> Options {
+    let env = |name: &str| std::env::var(name).ok().filter(|value| !value.is_empty());
+
+    let hub_path = cli.hub_path.clone()
+        .or_else(|| env("LOCAL_GIT_RS_HUB").map(PathBuf::from))
+        .unwrap_or_else(|| {
+            // Default to ~/.local-git-hub
+            let home = std::env::var("HOME").unwrap_or_else(|_| ".".to_string());
+            PathBuf::from(home).join(".local-git-hub")
+        });
+
+    let assume_yes = cli.assume_yes
+        || env("LOCAL_GIT_RS_NO_CONFIRM")
+            .is_some_and(|value| matches!(value.to_lowercase().as_str(), "1" | "true" | "yes" | "on"));
+
+    let color = cli.color
+        .or_else(|| env("LOCAL_GIT_RS_COLOR").and_then(|value| ColorChoice::from_str(&value, true).ok()))
+        .unwrap_or(ColorChoice::Auto);
+
+    Options {
+        hub_path,
+        assume_yes,
+        color,
+        format: env("LOCAL_GIT_RS_FORMAT"),
     }
 }
 
+/// Pick the --format flag, else LOCAL_GIT_RS_FORMAT if valid for this command, else the default
+fn resolve_format<T: ValueEnum>(flag: Option<T>, env: Option<&str>, default: T) -> T {
+    flag.or_else(|| env.and_then(|value| T::from_str(value, true).ok()))
+        .unwrap_or(default)
+}
+
 fn print_success(message: &str) {
     println!("{} {}", "✓".green(), message);
 }
@@ -141,164 +681,1398 @@ fn print_header(title: &str) {
     println!("{}", "=".repeat(title.len()).cyan());
 }
 
+/// Ask for confirmation, defaulting to no
+/// Auto-accepts with --yes; every prompt guards a destructive operation, so when stdin is not a
+/// terminal it fails instead of hanging or silently agreeing
+fn confirm(prompt: &str, assume_yes: bool) -> Result<bool> {
+    if assume_yes {
+        return Ok(true);
+    }
+
+    if !std::io::stdin().is_terminal() {
+        print_error(&format!("Cannot ask for confirmation, stdin is not a terminal: {}", prompt));
+        print_info("Pass --yes (or set LOCAL_GIT_RS_NO_CONFIRM=1) to confirm non-interactively");
+        anyhow::bail!("Confirmation required");
+    }
+
+    let confirmed = Confirm::with_theme(&ColorfulTheme::default())
+        .with_prompt(prompt)
+        .default(false)
+        .interact()?;
+
+    Ok(confirmed)
+}
+
+/// Let the user pick repositories from an interactive list showing name and size
+/// Requires a terminal, so scripts must pass repository names explicitly
+fn select_repos(hub: &LocalGitHub, options: InfoOptions, prompt: &str) -> Result<Vec<String>> {
+    if !std::io::stdin().is_terminal() || !std::io::stderr().is_terminal() {
+        anyhow::bail!("--select needs an interactive terminal, pass repository names instead");
+    }
+
+    let repos = hub.list_repos_with_info(InfoOptions { count_commits: false, ..options })?;
+    if repos.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let items: Vec<String> = repos
+        .iter()
+        .map(|repo| format!("{:<30} {:>12}", repo.name, format_size(repo.size, humansize::DECIMAL)))
+        .collect();
+
+    let chosen = MultiSelect::with_theme(&ColorfulTheme::default())
+        .with_prompt(prompt)
+        .items(&items)
+        .interact()?;
+
+    Ok(chosen.into_iter().map(|i| repos[i].name.clone()).collect())
+}
+
 fn format_datetime(dt: DateTime<Local>) -> String {
     dt.format("%Y-%m-%d %H:%M:%S").to_string()
 }
 
-fn main() -> Result<()> {
-    let cli = Cli::parse();
-    let hub_path = get_hub_path(cli.hub_path);
-
-    match cli.command {
-        Commands::Init => {
-            let hub = LocalGitHub::new(&hub_path);
-            hub.init()?;
-            print_success(&format!("Local Git Hub initialized at: {}", hub_path.display()));
-        }
+/// Parse a human-readable size such as "512", "100MB" or "1.5GiB" into bytes
+/// Decimal units (KB, MB, ...) are powers of 1000, binary units (KiB, MiB, ...) powers of 1024
+fn parse_size(value: &str) -> Result<u64, String> {
+    let value = value.trim();
+    let split = value
+        .find(|c: char| !(c.is_ascii_digit() || c == '.'))
+        .unwrap_or(value.len());
+    let (number, unit) = value.split_at(split);
 
-        Commands::Create { name } => {
-            let hub = LocalGitHub::new(&hub_path);
-            hub.init()?;
-            let repo_path = hub.create_repo(&name)?;
-            print_success(&format!("Repository '{}' created at: {}", name, repo_path.display()));
-            print_info(&format!("Use 'local-git-rs add-remote {}' to add to current project", name));
-        }
+    let number: f64 = number
+        .parse()
+        .map_err(|_| format!("'{}' is not a valid size", value))?;
 
-        Commands::List { detailed } => {
-            let hub = LocalGitHub::new(&hub_path);
+    let multiplier: u64 = match unit.trim().to_lowercase().as_str() {
+        "" | "b" => 1,
+        "k" | "kb" => 1_000,
+        "m" | "mb" => 1_000_000,
+        "g" | "gb" => 1_000_000_000,
+        "t" | "tb" => 1_000_000_000_000,
+        "kib" => 1 << 10,
+        "mib" => 1 << 20,
+        "gib" => 1 << 30,
+        "tib" => 1 << 40,
+        other => return Err(format!("unknown size unit '{}' (use B, KB, MB, GB, TB, KiB, MiB, GiB or TiB)", other)),
+    };
 
-            if detailed {
-                print_header("Repositories in Hub");
-                let repos = hub.list_repos_with_info()?;
+    Ok((number * multiplier as f64) as u64)
+}
 
-                if repos.is_empty() {
-                    print_warning("No repositories in hub");
-                    print_info("Use 'local-git-rs create <name>' to create new repository");
-                } else {
-                    println!(
-                        "{:<30} {:>12} {:>10} {:>20}",
-                        "Name".bold(),
-                        "Size".bold(),
-                        "Commits".bold(),
-                        "Modified".bold()
-                    );
-                    println!("{}", "-".repeat(75));
+/// Build a line matcher from a search pattern (literal unless `regex` is set)
+fn build_matcher(pattern: &str, regex: bool, ignore_case: bool) -> Result<regex::Regex> {
+    let expression = if regex { pattern.to_string() } else { regex::escape(pattern) };
 
-                    for repo in &repos {
-                        let size_str = format_size(repo.size, humansize::DECIMAL);
-                        let commits_str = repo.commits.map_or("N/A".to_string(), |c| c.to_string());
-                        let modified_str = format_datetime(repo.modified);
-
-                        println!(
-                            "{:<30} {:>12} {:>10} {:>20}",
-                            repo.name.dimmed(),
-                            size_str,
-                            commits_str.yellow(),
-                            modified_str.dimmed()
-                        );
-                    }
+    regex::RegexBuilder::new(&expression)
+        .case_insensitive(ignore_case)
+        .build()
+        .map_err(|e| anyhow::anyhow!("Invalid pattern '{}': {}", pattern, e))
+}
 
-                    println!("\nTotal: {} repositories", repos.len());
-                }
-            } else {
-                let repos = hub.list_repos()?;
+/// Print repositories grouped by top-level namespace, with per-namespace count and size
+fn print_repo_tree(repos: &[RepoInfo], bytes: bool) {
+    let mut top_level = Vec::new();
+    let mut namespaces: BTreeMap<&str, Vec<(&str, &RepoInfo)>> = BTreeMap::new();
 
-                if repos.is_empty() {
-                    print_warning("No repositories in hub");
-                    print_info("Use 'local-git-rs create <name>' to create new repository");
-                } else {
-                    print_header("Repositories in Hub");
-                    for repo in &repos {
-                        println!("  {}", repo.green());
-                    }
-                    println!("\nTotal: {} repositories", repos.len());
-                }
-            }
+    for repo in repos {
+        match repo.name.split_once('/') {
+            Some((namespace, rest)) => namespaces.entry(namespace).or_default().push((rest, repo)),
+            None => top_level.push(repo),
         }
+    }
 
-        Commands::Search { pattern } => {
-            let hub = LocalGitHub::new(&hub_path);
-            let repos = hub.search_repos(&pattern)?;
+    for repo in top_level {
+        println!(
+            "  {:<40} {:>12}{}",
+            tree_name(&repo.name, repo),
+            display_size(repo.size, bytes),
+            worktree_marker(repo)
+        );
+    }
 
-            print_header(&format!("Search Results for '{}'", pattern));
+    for (namespace, members) in &namespaces {
+        let total: u64 = members.iter().map(|(_, repo)| repo.size).sum();
+        println!(
+            "  {:<40} {:>12}  {}",
+            format!("{}/", namespace).cyan().bold(),
+            display_size(total, bytes),
+            format!("({} repositories)", members.len()).dimmed()
+        );
 
-            if repos.is_empty() {
-                print_warning("No repositories found");
-            } else {
-                for repo in &repos {
-                    println!("  {}", repo.green());
-                }
-                println!("\nFound: {} repositories", repos.len());
-            }
+        for (i, (name, repo)) in members.iter().enumerate() {
+            let branch = if i + 1 == members.len() { "└──" } else { "├──" };
+            println!(
+                "  {} {:<36} {:>12}{}",
+                branch.dimmed(),
+                tree_name(name, repo),
+                display_size(repo.size, bytes),
+                worktree_marker(repo)
+            );
         }
+    }
+}
 
-        Commands::Info { name } => {
-            let hub = LocalGitHub::new(&hub_path);
+/// Shorten text to at most `width` characters, ending with an ellipsis if cut
+fn truncate(text: &str, width: usize) -> String {
+    if text.chars().count() <= width {
+        text.to_string()
+    } else {
+        let kept: String = text.chars().take(width.saturating_sub(1)).collect();
+        format!("{}…", kept)
+    }
+}
 
-            if !hub.repo_exists(&name) {
-                print_error(&format!("Repository '{}' does not exist", name));
-                anyhow::bail!("Repository not found");
-            }
+/// Drop the .git suffix from a displayed repository name
+fn strip_git_suffix(name: &str) -> &str {
+    name.strip_suffix(".git").unwrap_or(name)
+}
 
-            let info = hub.get_repo_info(&name)?;
+/// Drop the .git suffix from the names of listed repositories
+fn strip_repo_suffixes(repos: &mut [RepoInfo]) {
+    for repo in repos {
+        repo.name = strip_git_suffix(&repo.name).to_string();
+    }
+}
 
-            print_header(&format!("Repository: {}", info.name));
-            println!("  Path:     {}", info.path.display().to_string().dimmed());
-            println!("  Size:     {}", format_size(info.size, humansize::DECIMAL).cyan());
-            println!("  Commits:  {}", info.commits.map_or("N/A".to_string(), |c| c.to_string()).yellow());
-            println!("  Modified: {}", format_datetime(info.modified).dimmed());
-        }
+/// Format a size for display, as a raw byte count when requested (for scripts)
+fn display_size(size: u64, bytes: bool) -> String {
+    if bytes {
+        size.to_string()
+    } else {
+        format_size(size, humansize::DECIMAL)
+    }
+}
 
-        Commands::Delete { name, force } => {
-            let hub = LocalGitHub::new(&hub_path);
+/// Pad and join the cells of a detailed table row; a trailing text column is not padded
+fn format_row(columns: &[Column], cells: Vec<ColoredString>) -> String {
+    let last = columns.len() - 1;
 
-            if !hub.repo_exists(&name) {
-                print_error(&format!("Repository '{}' does not exist", name));
-                anyhow::bail!("Repository not found");
+    columns
+        .iter()
+        .zip(cells)
+        .enumerate()
+        .map(|(i, (column, cell))| {
+            let width = column.width();
+            if column.left_aligned() && i == last {
+                cell.to_string()
+            } else if column.left_aligned() {
+                format!("{:<width$}", cell)
+            } else {
+                format!("{:>width$}", cell)
             }
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
 
-            // Get repository info before deletion
-            let info = hub.get_repo_info(&name)?;
-
-            if !force {
-                print_warning(&format!("You are about to delete repository '{}'", name));
-                println!("  Size:    {}", format_size(info.size, humansize::DECIMAL));
-                println!("  Commits: {}", info.commits.map_or("N/A".to_string(), |c| c.to_string()));
+/// One-line repository summary, e.g. `foo.git 12MB 34 commits (2d ago)`
+/// The age is taken from the latest commit, or the directory modification time without commits
+fn short_summary(info: &RepoInfo, bytes: bool) -> String {
+    let size = if bytes {
+        info.size.to_string()
+    } else {
+        format_size(info.size, humansize::DECIMAL.space_after_value(false).decimal_places(0))
+    };
 
-                let confirm = Confirm::with_theme(&ColorfulTheme::default())
-                    .with_prompt("Are you sure you want to delete this repository?")
-                    .default(false)
-                    .interact()?;
+    let mut summary = format!("{} {}", info.name, size);
+    if let Some(commits) = info.commits {
+        summary.push_str(&format!(" {} commits", commits));
+    }
+    summary.push_str(&format!(" ({} ago)", format_age(info.last_commit.unwrap_or(info.modified))));
 
-                if !confirm {
-                    print_info("Deletion cancelled");
-                    return Ok(());
-                }
-            }
+    summary
+}
 
-            hub.delete_repo(&name)?;
-            print_success(&format!("Repository '{}' deleted", name));
-        }
+/// Compact age of a timestamp in the largest whole unit (e.g., "45s", "3h", "2d")
+fn format_age(dt: DateTime<Local>) -> String {
+    let seconds = (Local::now() - dt).num_seconds().max(0);
+
+    match seconds {
+        0..60 => format!("{}s", seconds),
+        60..3600 => format!("{}m", seconds / 60),
+        3600..86400 => format!("{}h", seconds / 3600),
+        _ => format!("{}d", seconds / 86400),
+    }
+}
+
+/// Print the footer of detailed listings with the repository count and total size
+fn print_total(repos: &[RepoInfo], bytes: bool) {
+    let total: u64 = repos.iter().map(|repo| repo.size).sum();
+    println!("\nTotal: {} repositories, {}", repos.len(), display_size(total, bytes));
+}
+
+/// Explain an empty listing, unless --quiet-empty leaves it to the exit status
+fn print_no_repos(filtered: bool, quiet: bool) {
+    if quiet {
+        return;
+    }
+
+    if filtered {
+        print_warning("No repositories match the filters");
+    } else {
+        print_warning("No repositories in hub");
+        print_info("Use 'local-git-rs create <name>' to create new repository");
+    }
+}
+
+/// Warn that some sizes are partial because entries could not be read (permission denied)
+fn warn_unreadable(repos: &[RepoInfo]) {
+    let partial: Vec<&RepoInfo> = repos.iter().filter(|repo| repo.unreadable > 0).collect();
+    if partial.is_empty() {
+        return;
+    }
+
+    let skipped: usize = partial.iter().map(|repo| repo.unreadable).sum();
+    print_warning(&format!(
+        "Skipped {} unreadable entries (permission denied), sizes of {} repositories are partial",
+        skipped,
+        partial.len()
+    ));
+}
+
+/// List repository names, warning about namespaces that cannot be read instead of failing
+fn list_repo_names(hub: &LocalGitHub) -> Result<Vec<String>> {
+    let mut names = Vec::new();
+    for name in hub.iter_repo_names() {
+        match name {
+            Ok(name) => names.push(name),
+            Err(e) if e.is::<UnreadableNamespace>() => print_warning(&format!("{:#}", e)),
+            Err(e) => return Err(e),
+        }
+    }
+
+    names.sort();
+    Ok(names)
+}
+
+/// Warn about forks whose sizes leave out the objects they borrow via alternates
+fn warn_alternates(repos: &[RepoInfo]) {
+    let forks: Vec<&str> = repos
+        .iter()
+        .filter(|repo| !repo.alternates.is_empty())
+        .map(|repo| repo.name.as_str())
+        .collect();
+    if forks.is_empty() {
+        return;
+    }
+
+    print_warning(&format!(
+        "{} repositories borrow objects via alternates, so their sizes understate storage and deleting the source breaks them: {}",
+        forks.len(),
+        forks.join(", ")
+    ));
+}
+
+/// Color a repository name for tree output, red if its information is unavailable
+fn tree_name(name: &str, repo: &RepoInfo) -> ColoredString {
+    if repo.error.is_some() { name.red() } else { name.green() }
+}
+
+/// Mark repositories with a working tree in tree output, like the flat list does
+fn worktree_marker(repo: &RepoInfo) -> String {
+    if repo.bare { String::new() } else { format!("  {}", "[worktree]".yellow()) }
+}
+
+/// Print git operation timings per repository to stderr, slowest first
+fn print_profile(repos: &[RepoInfo]) {
+    let mut ranked: Vec<&RepoInfo> = repos.iter().collect();
+    ranked.sort_by_key(|repo| std::cmp::Reverse(repo.timing.total()));
+
+    eprintln!("\n{}", "Profile (slowest first)".bold().cyan());
+    eprintln!("{:<30} {:>12} {:>12} {:>12}", "Name", "Open", "Walk", "Total");
+
+    for repo in ranked {
+        eprintln!(
+            "{:<30} {:>10.1}ms {:>10.1}ms {:>10.1}ms",
+            repo.name,
+            repo.timing.open.as_secs_f64() * 1000.0,
+            repo.timing.walk.as_secs_f64() * 1000.0,
+            repo.timing.total().as_secs_f64() * 1000.0
+        );
+    }
+}
+
+/// Write repositories as CSV to stdout
+fn print_repos_csv(repos: &[RepoInfo]) -> Result<()> {
+    let mut writer = csv::Writer::from_writer(std::io::stdout());
+    writer.write_record(["name", "size_bytes", "commits", "modified"])?;
+
+    for repo in repos {
+        writer.write_record([
+            repo.name.clone(),
+            repo.size.to_string(),
+            repo.commits.map_or(String::new(), |c| c.to_string()),
+            repo.modified.to_rfc3339(),
+        ])?;
+    }
+
+    writer.flush()?;
+    Ok(())
+}
+
+fn main() -> Result<()> {
+    let cli = Cli::parse();
+    let options = resolve_options(&cli);
+    let hub_path = options.hub_path;
+
+    match options.color {
+        ColorChoice::Always => colored::control::set_override(true),
+        ColorChoice::Never => colored::control::set_override(false),
+        ColorChoice::Auto => {}
+    }
+
+    let size_mode = if cli.disk { SizeMode::Disk } else { SizeMode::Apparent };
+    let info_options = InfoOptions { size_mode, count_commits: true };
+    let exact_names = cli.no_suffix_match;
+    let assume_yes = options.assume_yes;
+    let profile = cli.profile;
+    let verbose = cli.verbose;
+
+    match cli.command {
+        Commands::Init => {
+            let hub = LocalGitHub::new(&hub_path).exact_names(exact_names);
+            hub.init()?;
+            print_success(&format!("Local Git Hub initialized at: {}", hub_path.display()));
+        }
+
+        Commands::Create {
+            name,
+            with_worktree,
+            from_hooks,
+            reflog,
+            template,
+            description,
+            shared,
+            jobs,
+        } => {
+            let hub = LocalGitHub::new(&hub_path)
+                .exact_names(exact_names)
+                .shared(shared.map(Shared::mode));
+            hub.init()?;
+
+            if let Some(source) = &from_hooks
+                && !hub.repo_exists(source)
+            {
+                print_error(&format!("Hook source repository '{}' does not exist", source));
+                anyhow::bail!("Repository not found");
+            }
+
+            let create = |name: &str| -> Result<PathBuf> {
+                let repo_path = if with_worktree {
+                    hub.create_worktree_repo(name)?
+                } else {
+                    hub.create_repo(name)?
+                };
+
+                if reflog {
+                    hub.enable_reflog(name)?;
+                }
+
+                if let Some(description) = &description {
+                    hub.set_description(name, description)?;
+                }
+
+                if let Some(source) = &from_hooks {
+                    let copied = hub.copy_hooks(source, name)?;
+                    print_info(&format!("Copied {} hooks from '{}'", copied, source));
+                }
+
+                if let Some(template) = &template {
+                    // Don't leave a half-initialized repository behind
+                    let commits = hub.seed_repo(name, template, verbose).inspect_err(|_| {
+                        let _ = hub.delete_repo(name, true);
+                    })?;
+                    print_info(&format!(
+                        "Seeded from '{}' ({} commits)",
+                        template,
+                        commits.map_or("N/A".to_string(), |c| c.to_string())
+                    ));
+                }
+
+                Ok(repo_path)
+            };
+
+            if name != "-" {
+                let repo_path = create(&name)?;
+                print_success(&format!("Repository '{}' created at: {}", name, repo_path.display()));
+                print_info(&format!("Use 'local-git-rs add-remote {}' to add to current project", name));
+                return Ok(());
+            }
+
+            // Bulk mode: keep going past individual failures and summarize at the end
+            let skipped = AtomicUsize::new(0);
+            let mut seen = HashSet::new();
+            let mut names = Vec::new();
+            for line in std::io::stdin().lines() {
+                let line = line?;
+                let name = line.trim();
+                if name.is_empty() {
+                    continue;
+                }
+
+                // 'foo' and 'foo.git' name the same repository
+                if !seen.insert(LocalGitHub::with_git_suffix(name)) {
+                    print_warning(&format!("Repository '{}' is listed more than once, skipped", name));
+                    skipped.fetch_add(1, Ordering::Relaxed);
+                    continue;
+                }
+                names.push(name.to_string());
+            }
+
+            let (created, failed) = (AtomicUsize::new(0), AtomicUsize::new(0));
+            let next = AtomicUsize::new(0);
+            let start = Instant::now();
+
+            // Workers take the next unclaimed name until the batch is exhausted
+            std::thread::scope(|scope| {
+                for _ in 0..(jobs as usize).min(names.len()) {
+                    scope.spawn(|| {
+                        while let Some(name) = names.get(next.fetch_add(1, Ordering::Relaxed)) {
+                            if hub.repo_exists(name) {
+                                print_warning(&format!("Repository '{}' already exists, skipped", name));
+                                skipped.fetch_add(1, Ordering::Relaxed);
+                                continue;
+                            }
+
+                            match create(name) {
+                                Ok(repo_path) => {
+                                    print_success(&format!("Repository '{}' created at: {}", name, repo_path.display()));
+                                    created.fetch_add(1, Ordering::Relaxed);
+                                }
+                                Err(e) => {
+                                    print_error(&format!("Failed to create '{}': {}", name, e));
+                                    failed.fetch_add(1, Ordering::Relaxed);
+                                }
+                            }
+                        }
+                    });
+                }
+            });
+
+            let (created, skipped, failed) = (created.into_inner(), skipped.into_inner(), failed.into_inner());
+            println!("\nCreated: {}, skipped (existing or duplicate): {}, failed: {}", created, skipped, failed);
+
+            if profile {
+                let elapsed = start.elapsed().as_secs_f64();
+                eprintln!(
+                    "Created {} repositories in {:.2}s ({:.1} repos/sec, {} jobs)",
+                    created,
+                    elapsed,
+                    created as f64 / elapsed.max(f64::EPSILON),
+                    jobs
+                );
+            }
+            if failed > 0 {
+                anyhow::bail!("{} repositories could not be created", failed);
+            }
+        }
+
+        Commands::ValidateName { name } => {
+            let hub = LocalGitHub::new(&hub_path).exact_names(exact_names);
+
+            if let Err(e) = hub.validate_repo_name(&name) {
+                print_error(&format!("Invalid repository name '{}': {}", name, e));
+                anyhow::bail!("Invalid repository name");
+            }
+
+            print_success(&format!(
+                "Repository name '{}' is valid (stored as '{}')",
+                name,
+                LocalGitHub::with_git_suffix(&name)
+            ));
+        }
+
+        Commands::List {
+            detailed,
+            format,
+            tree,
+            min_size,
+            max_size,
+            size_only,
+            empty,
+            bytes,
+            no_suffix,
+            age_from,
+            from_index,
+            with_origin,
+            columns,
+            quiet_empty,
+        } => {
+            let hub = LocalGitHub::new(&hub_path).exact_names(exact_names);
+            let list_options = InfoOptions { count_commits: !size_only, ..info_options };
+            let filtered = min_size.is_some() || max_size.is_some() || empty;
+            let filter_matches = |repo: &RepoInfo| {
+                min_size.is_none_or(|min| repo.size >= min)
+                    && max_size.is_none_or(|max| repo.size <= max)
+                    && (!empty || repo.empty)
+            };
+
+            let format = resolve_format(format, options.format.as_deref(), ListFormat::Table);
+            let found = if format == ListFormat::Csv {
+                let mut repos = hub.list_repos_with_info(list_options)?;
+                repos.retain(|repo| filter_matches(repo));
+                if no_suffix {
+                    strip_repo_suffixes(&mut repos);
+                }
+                print_repos_csv(&repos)?;
+
+                if profile {
+                    print_profile(&repos);
+                }
+                repos.len()
+            } else if tree && hub.list_repos()?.iter().any(|name| name.contains('/')) {
+                let mut repos = hub.list_repos_with_info(list_options)?;
+                repos.retain(|repo| filter_matches(repo));
+                if no_suffix {
+                    strip_repo_suffixes(&mut repos);
+                }
+
+                if repos.is_empty() {
+                    print_no_repos(filtered, quiet_empty);
+                } else {
+                    print_header("Repositories in Hub");
+                    print_repo_tree(&repos, bytes);
+                    print_total(&repos, bytes);
+                    warn_unreadable(&repos);
+                    warn_alternates(&repos);
+                }
+
+                if profile {
+                    print_profile(&repos);
+                }
+                repos.len()
+            } else if detailed {
+                let mut repos = hub.list_repos_with_info(list_options)?;
+                repos.retain(|repo| filter_matches(repo));
+                if no_suffix {
+                    strip_repo_suffixes(&mut repos);
+                }
+
+                if repos.is_empty() {
+                    print_no_repos(filtered, quiet_empty);
+                } else {
+                    print_header("Repositories in Hub");
+                    let mut columns = columns.unwrap_or_else(|| Column::DEFAULT.to_vec());
+                    if with_origin && !columns.contains(&Column::Origin) {
+                        columns.push(Column::Origin);
+                    }
+
+                    let headers: Vec<ColoredString> = columns
+                        .iter()
+                        .map(|column| column.header(age_from).bold())
+                        .collect();
+                    println!("{}", format_row(&columns, headers));
+                    let width: usize = columns.iter().map(|column| column.width()).sum();
+                    println!("{}", "-".repeat(width + columns.len() - 1));
+
+                    for repo in &repos {
+                        let cells = columns
+                            .iter()
+                            .map(|column| column.cell(repo, age_from, bytes))
+                            .collect();
+                        println!("{}", format_row(&columns, cells));
+
+                        if let Some(error) = &repo.error {
+                            println!("  {}", error.red());
+                        }
+                    }
+
+                    print_total(&repos, bytes);
+                    warn_unreadable(&repos);
+                    warn_alternates(&repos);
+                }
+
+                if profile {
+                    print_profile(&repos);
+                }
+                repos.len()
+            } else {
+                let repos = if filtered {
+                    hub.list_repos_with_info(InfoOptions { count_commits: false, ..info_options })?
+                        .into_iter()
+                        .filter(|repo| filter_matches(repo))
+                        .map(|repo| repo.name)
+                        .collect()
+                } else if from_index {
+                    match hub.read_index()? {
+                        Some(index) => {
+                            print_info(&format!("Using index from {}", format_datetime(index.generated)));
+                            index.repos
+                        }
+                        None => {
+                            print_error("No index found for this hub");
+                            print_info("Use 'local-git-rs index' to create it");
+                            anyhow::bail!("Index not found");
+                        }
+                    }
+                } else {
+                    list_repo_names(&hub)?
+                };
+
+                if repos.is_empty() {
+                    print_no_repos(filtered, quiet_empty);
+                } else {
+                    print_header("Repositories in Hub");
+                    for repo in &repos {
+                        let shown = if no_suffix { strip_git_suffix(repo) } else { repo };
+                        if hub.is_worktree_repo(repo) {
+                            println!("  {} {}", shown.green(), "[worktree]".yellow());
+                        } else {
+                            println!("  {}", shown.green());
+                        }
+                    }
+                    println!("\nTotal: {} repositories", repos.len());
+                }
+                repos.len()
+            };
+
+            if quiet_empty && found == 0 {
+                std::process::exit(1);
+            }
+        }
+
+        Commands::Search {
+            pattern,
+            author,
+            content,
+            no_suffix,
+            quiet_empty,
+        } => {
+            let hub = LocalGitHub::new(&hub_path).exact_names(exact_names);
+            let mut repos = hub.search_repos(pattern.as_deref().unwrap_or(""))?;
+
+            if content && let Some(pattern) = &pattern {
+                // Reading every README is slow, so report progress per repository
+                let show_progress = std::io::stderr().is_terminal();
+                let described = hub.search_readme(pattern, |i, total, name| {
+                    if show_progress {
+                        eprint!("\r\x1b[2K{} [{}/{}] {}", "Reading READMEs".dimmed(), i + 1, total, name);
+                    }
+                })?;
+                if show_progress {
+                    eprint!("\r\x1b[2K");
+                }
+
+                for name in described {
+                    if !repos.contains(&name) {
+                        repos.push(name);
+                    }
+                }
+                repos.sort();
+            }
+
+            if let Some(author) = &author {
+                let authored = hub.search_by_author(author)?;
+                repos.retain(|repo| authored.contains(repo));
+            }
+
+            if quiet_empty && repos.is_empty() {
+                std::process::exit(1);
+            }
+
+            let mut title = format!("Search Results for '{}'", pattern.as_deref().unwrap_or("*"));
+            if let Some(author) = &author {
+                title.push_str(&format!(" (author: '{}')", author));
+            }
+            print_header(&title);
+
+            if repos.is_empty() {
+                print_warning("No repositories found");
+            } else {
+                for repo in &repos {
+                    let shown = if no_suffix { strip_git_suffix(repo) } else { repo };
+                    println!("  {}", shown.green());
+                }
+                println!("\nFound: {} repositories", repos.len());
+            }
+        }
+
+        Commands::GrepLog {
+            pattern,
+            regex,
+            ignore_case,
+            repo,
+        } => {
+            let hub = LocalGitHub::new(&hub_path).exact_names(exact_names);
+
+            let matcher = build_matcher(&pattern, regex, ignore_case)?;
+
+            let repos = hub.search_repos(repo.as_deref().unwrap_or(""))?;
+            let show_progress = std::io::stderr().is_terminal();
+            let mut matches = Vec::new();
+
+            // History walks are slow, so report progress per repository
+            for (i, name) in repos.iter().enumerate() {
+                if show_progress {
+                    eprint!("\r\x1b[2K{} [{}/{}] {}", "Searching".dimmed(), i + 1, repos.len(), name);
+                }
+                // One broken repository (dangling ref, missing object) must not stop the search
+                match hub.search_commit_messages(name, &matcher) {
+                    Ok(found) => matches.extend(found),
+                    Err(e) => {
+                        if show_progress {
+                            eprint!("\r\x1b[2K");
+                        }
+                        eprintln!("{} Skipping '{}': {:#}", "⚠".yellow(), name, e);
+                    }
+                }
+            }
+            if show_progress {
+                eprint!("\r\x1b[2K");
+            }
+
+            print_header(&format!("Commits matching '{}'", pattern));
+
+            if matches.is_empty() {
+                print_warning("No matching commits found");
+            } else {
+                for m in &matches {
+                    println!("  {}:{} {}", m.repo.green(), m.short_id.yellow(), m.summary);
+                }
+                println!("\nFound: {} commits in {} repositories", matches.len(), repos.len());
+            }
+        }
+
+        Commands::Grep {
+            name,
+            pattern,
+            rev,
+            regex,
+            ignore_case,
+        } => {
+            let hub = LocalGitHub::new(&hub_path).exact_names(exact_names);
+
+            if !hub.repo_exists(&name) {
+                print_error(&format!("Repository '{}' does not exist", name));
+                anyhow::bail!("Repository not found");
+            }
+
+            let matcher = build_matcher(&pattern, regex, ignore_case)?;
+            let hits = hub.grep(&name, &matcher, rev.as_deref())?;
+
+            if hits.is_empty() {
+                print_warning("No matches found");
+            } else {
+                for hit in &hits {
+                    println!("{}:{}:{}", hit.path.green(), hit.line_number.to_string().yellow(), hit.line);
+                }
+            }
+        }
+
+        Commands::Info {
+            name,
+            include_remotes,
+            refname,
+            format,
+            size_only,
+            upstream,
+            bytes,
+        } => {
+            let hub = LocalGitHub::new(&hub_path).exact_names(exact_names);
+
+            if !hub.repo_exists(&name) {
+                print_error(&format!("Repository '{}' does not exist", name));
+                anyhow::bail!("Repository not found");
+            }
+
+            // The format may come from the config file, which clap cannot check against the flags
+            let format = resolve_format(format, options.format.as_deref(), InfoFormat::Text);
+            if format != InfoFormat::Text && (refname.is_some() || upstream || include_remotes) {
+                print_error("--ref, --upstream and --include-remotes are only supported with the text format");
+                anyhow::bail!("Conflicting options");
+            }
+
+            let info = hub.get_repo_info(&name, InfoOptions { count_commits: !size_only, ..info_options })?;
+
+            if profile {
+                print_profile(std::slice::from_ref(&info));
+            }
+
+            match format {
+                InfoFormat::Toml => {
+                    print!("{}", toml::to_string(&info)?);
+                    return Ok(());
+                }
+                InfoFormat::Short => {
+                    println!("{}", short_summary(&info, bytes));
+                    return Ok(());
+                }
+                InfoFormat::Text => {}
+            }
+
+            let is_empty = hub.is_empty_repo(&name);
+            if is_empty && refname.is_some() {
+                print_warning(&format!("Repository '{}' is empty, push a commit first", name));
+                anyhow::bail!("Repository has no refs");
+            }
+
+            let ref_summary = refname
+                .as_deref()
+                .map(|refname| hub.ref_summary(&name, refname))
+                .transpose()?;
+
+            print_header(&format!("Repository: {}", info.name));
+            println!("  Path:     {}", info.path.display().to_string().dimmed());
+            println!("  Type:     {}", if info.bare { "bare" } else { "worktree" });
+            if info.unreadable > 0 {
+                println!(
+                    "  Size:     {} {}",
+                    display_size(info.size, bytes).cyan(),
+                    format!("(partial, {} entries unreadable)", info.unreadable).yellow()
+                );
+            } else {
+                println!("  Size:     {}", display_size(info.size, bytes).cyan());
+            }
+            let commits_str = match info.commits {
+                Some(commits) => commits.to_string(),
+                None if is_empty => "empty (no commits yet)".to_string(),
+                None => "N/A".to_string(),
+            };
+            println!("  Commits:  {}", commits_str.yellow());
+            println!("  Modified: {}", format_datetime(info.modified).dimmed());
+            if let Some(error) = &info.error {
+                println!("  Error:    {}", error.red());
+            }
+            for alternate in &info.alternates {
+                println!("  Borrows:  {}", alternate.display().to_string().yellow());
+            }
+            if !info.alternates.is_empty() {
+                print_warning("Objects are shared via alternates: the size above excludes them, and deleting the source repository breaks this one");
+                print_info(&format!("Use 'local-git-rs fork --detach {}' to make it independent", name));
+            }
+
+            let refs = hub.ref_storage(&name)?;
+            println!("  Refs:     {} loose, {} packed", refs.loose, refs.packed);
+            if refs.loose > LOOSE_REFS_WARNING {
+                print_warning(&format!("Many loose refs, run 'local-git-rs pack-refs {}' to speed up ref lookups", name));
+            }
+
+            if let Some(summary) = ref_summary {
+                println!("  Ref:      {} ({})", summary.refname.cyan(), summary.short_id.yellow());
+                println!("    Commits: {}", summary.commits.map_or("N/A".to_string(), |c| c.to_string()).yellow());
+                println!("    Author:  {}", summary.author);
+                println!("    Date:    {}", format_datetime(summary.date).dimmed());
+            }
+
+            if upstream {
+                let divergence = hub.upstream_divergence(&name)?;
+
+                if divergence.is_empty() {
+                    println!("  Upstream: {}", "no branches".dimmed());
+                } else {
+                    println!("  Upstream:");
+                    for branch in divergence {
+                        let status = match branch.ahead_behind {
+                            None => "no origin counterpart".dimmed(),
+                            Some((0, 0)) => "up to date".green(),
+                            Some((ahead, behind)) => format!("{} ahead, {} behind", ahead, behind).yellow(),
+                        };
+                        println!("    {} {}", branch.branch.cyan(), status);
+                    }
+                }
+            }
+
+            if include_remotes {
+                let remotes = RemoteManager::list_remotes(Some(&info.path))?;
+
+                if remotes.is_empty() {
+                    println!("  Remotes:  {}", "none".dimmed());
+                } else {
+                    println!("  Remotes:");
+                    for (name, url) in remotes {
+                        println!("    {} -> {}", name.cyan(), url.dimmed());
+                    }
+                }
+            }
+        }
+
+        Commands::TestRestore { name } => {
+            let hub = LocalGitHub::new(&hub_path).exact_names(exact_names);
+
+            if !hub.repo_exists(&name) {
+                print_error(&format!("Repository '{}' does not exist", name));
+                anyhow::bail!("Repository not found");
+            }
+
+            print_info(&format!("Cloning '{}' into a temporary directory...", name));
+            let report = hub.test_restore(&name)?;
+
+            if report.head_matches {
+                print_success(&format!("Clone HEAD matches source: {}", report.commit));
+            } else {
+                print_error(&format!("Clone HEAD does not match source HEAD {}", report.commit));
+            }
+
+            if report.fsck_passed {
+                print_success("git fsck passed on the clone");
+            } else {
+                print_error("git fsck reported problems on the clone:");
+                println!("{}", report.fsck_output.dimmed());
+            }
+
+            if !report.passed() {
+                anyhow::bail!("Restore test failed");
+            }
+            print_success(&format!("Repository '{}' can be restored", name));
+        }
+
+        Commands::Reflog { name, refname } => {
+            let hub = LocalGitHub::new(&hub_path).exact_names(exact_names);
+
+            if !hub.repo_exists(&name) {
+                print_error(&format!("Repository '{}' does not exist", name));
+                anyhow::bail!("Repository not found");
+            }
+
+            let entries = hub.read_reflog(&name, &refname)?;
+
+            if entries.is_empty() {
+                print_warning(&format!("No reflog entries for '{}'", refname));
+                if !hub.reflog_enabled(&name)? {
+                    print_info(&format!(
+                        "Reflogs are off for this repository, enable them with 'git --git-dir {} config core.logAllRefUpdates true'",
+                        hub.get_repo_path(&name)?.display()
+                    ));
+                }
+            } else {
+                print_header(&format!("Reflog of {} in '{}'", refname, name));
+                for entry in &entries {
+                    println!(
+                        "  {}..{} {} {} {}",
+                        entry.old_id.dimmed(),
+                        entry.new_id.yellow(),
+                        format_datetime(entry.date).dimmed(),
+                        entry.committer.cyan(),
+                        entry.message
+                    );
+                }
+            }
+        }
+
+        Commands::BranchSizes { name } => {
+            let hub = LocalGitHub::new(&hub_path).exact_names(exact_names);
+
+            if !hub.repo_exists(&name) {
+                print_error(&format!("Repository '{}' does not exist", name));
+                anyhow::bail!("Repository not found");
+            }
+
+            if hub.is_empty_repo(&name) {
+                print_warning(&format!("Repository '{}' is empty (no commits yet)", name));
+                return Ok(());
+            }
+
+            let sizes = hub.branch_sizes(&name)?;
+
+            print_header(&format!("Branch sizes in '{}' (objects not reachable from HEAD)", name));
+
+            if sizes.is_empty() {
+                print_warning("No branches besides the default branch");
+            } else {
+                for (branch, size) in &sizes {
+                    println!("  {:<40} {:>12}", branch.cyan(), format_size(*size, humansize::DECIMAL));
+                }
+            }
+        }
+
+        Commands::FindDuplicates => {
+            let hub = LocalGitHub::new(&hub_path).exact_names(exact_names);
+            let duplicates = hub.find_duplicates()?;
+
+            print_header("Duplicate Repositories");
+
+            if duplicates.is_empty() {
+                print_success("No duplicate repositories found");
+                return Ok(());
+            }
+
+            let size_options = InfoOptions { count_commits: false, ..info_options };
+            let mut reclaimable = 0;
+            for cluster in &duplicates {
+                let sizes = cluster
+                    .iter()
+                    .map(|name| hub.get_repo_info(name, size_options).map(|info| info.size))
+                    .collect::<Result<Vec<_>>>()?;
+
+                // Keeping the largest copy frees the space of all others
+                let kept = sizes.iter().copied().max().unwrap_or(0);
+                let cluster_reclaimable = sizes.iter().sum::<u64>() - kept;
+                reclaimable += cluster_reclaimable;
+
+                println!("{} copies, {} reclaimable:", cluster.len(), display_size(cluster_reclaimable, false).yellow());
+                for (name, size) in cluster.iter().zip(&sizes) {
+                    println!("  {:<30} {:>12}", name.green(), display_size(*size, false));
+                }
+            }
+
+            println!(
+                "\nTotal: {} clusters, {} reclaimable",
+                duplicates.len(),
+                display_size(reclaimable, false)
+            );
+            print_info("Keep one copy and recreate the others with 'local-git-rs fork <kept> <name>' to share its objects");
+        }
+
+        Commands::DeleteRef { name, refname } => {
+            let hub = LocalGitHub::new(&hub_path).exact_names(exact_names);
+
+            if !hub.repo_exists(&name) {
+                print_error(&format!("Repository '{}' does not exist", name));
+                anyhow::bail!("Repository not found");
+            }
+
+            let (full_name, oid) = hub.find_ref(&name, &refname)?;
+
+            print_warning(&format!("You are about to delete '{}' in '{}'", full_name, name));
+            println!("  Points at: {}", oid.to_string().yellow());
+
+            if !confirm("Are you sure you want to delete this ref?", assume_yes)? {
+                print_info("Deletion cancelled");
+                return Ok(());
+            }
+
+            let (full_name, oid) = hub.delete_ref(&name, &full_name)?;
+
+            print_success(&format!("Deleted '{}' (was {})", full_name, oid));
+            print_info(&format!(
+                "Restore with 'git --git-dir {} update-ref {} {}' while the objects are not yet pruned",
+                hub.get_repo_path(&name)?.display(),
+                full_name,
+                oid
+            ));
+        }
+
+        Commands::RenameRef { name, old, new } => {
+            let hub = LocalGitHub::new(&hub_path).exact_names(exact_names);
+
+            if !hub.repo_exists(&name) {
+                print_error(&format!("Repository '{}' does not exist", name));
+                anyhow::bail!("Repository not found");
+            }
+
+            let (old_name, new_name) = hub.rename_ref(&name, &old, &new)?;
+            print_success(&format!("Renamed '{}' to '{}' in '{}'", old_name, new_name, name));
+        }
+
+        Commands::PackRefs { name } => {
+            let hub = LocalGitHub::new(&hub_path).exact_names(exact_names);
+
+            if !hub.repo_exists(&name) {
+                print_error(&format!("Repository '{}' does not exist", name));
+                anyhow::bail!("Repository not found");
+            }
+
+            let (before, after) = hub.pack_refs(&name)?;
+
+            print_success(&format!("Packed refs in '{}'", name));
+            println!("  Before: {} loose, {} packed", before.loose.to_string().yellow(), before.packed);
+            println!("  After:  {} loose, {} packed", after.loose.to_string().yellow(), after.packed);
+        }
+
+        Commands::CommitGraph { name } => {
+            let hub = LocalGitHub::new(&hub_path).exact_names(exact_names);
+
+            let names = match name {
+                Some(name) => {
+                    if !hub.repo_exists(&name) {
+                        print_error(&format!("Repository '{}' does not exist", name));
+                        anyhow::bail!("Repository not found");
+                    }
+                    vec![name]
+                }
+                None => hub.list_repos()?,
+            };
+
+            let mut failed = 0;
+            for name in &names {
+                match hub.write_commit_graph(name) {
+                    Ok(()) => print_success(&format!("Wrote commit-graph for '{}'", name)),
+                    Err(e) => {
+                        print_error(&format!("Failed to write commit-graph for '{}': {}", name, e));
+                        failed += 1;
+                    }
+                }
+            }
+
+            if failed > 0 {
+                anyhow::bail!("{} of {} repositories failed", failed, names.len());
+            }
+        }
+
+        Commands::Delete {
+            name,
+            force,
+            select,
+            keep_refs_backup,
+            no_verify,
+        } => {
+            let hub = LocalGitHub::new(&hub_path).exact_names(exact_names);
+
+            let names = if select {
+                select_repos(&hub, info_options, "Select repositories to delete")?
+            } else {
+                vec![name.expect("name is required without --select")]
+            };
+
+            if names.is_empty() {
+                print_info("No repositories selected");
+            }
+
+            for name in names {
+                if !hub.repo_exists(&name) {
+                    print_error(&format!("Repository '{}' does not exist", name));
+                    anyhow::bail!("Repository not found");
+                }
+
+                let forks = hub.find_forks_of(&name)?;
+                if !forks.is_empty() {
+                    print_error(&format!(
+                        "Repository '{}' cannot be deleted, these forks borrow its objects: {}",
+                        name,
+                        forks.join(", ")
+                    ));
+                    print_info("Detach each fork with 'local-git-rs fork --detach <fork>' (or delete it) first");
+                    anyhow::bail!("Repository has dependent forks");
+                }
+
+                // Get repository info before deletion
+                let info = hub.get_repo_info(&name, info_options)?;
+
+                if !force {
+                    print_warning(&format!("You are about to delete repository '{}'", name));
+                    println!("  Size:    {}", format_size(info.size, humansize::DECIMAL));
+                    println!("  Commits: {}", info.commits.map_or("N/A".to_string(), |c| c.to_string()));
+
+                    if !confirm("Are you sure you want to delete this repository?", assume_yes)? {
+                        print_info("Deletion cancelled");
+                        continue;
+                    }
+                }
+
+                if keep_refs_backup {
+                    let snapshot = hub.snapshot_refs(&name)?;
+                    print_info(&format!("Refs saved to: {}", snapshot.display()));
+                }
+
+                hub.delete_repo(&name, !no_verify)?;
+                print_success(&format!("Repository '{}' deleted", name));
+            }
+        }
+
+        Commands::Adopt { name } => {
+            let hub = LocalGitHub::new(&hub_path).exact_names(exact_names);
+            let new_path = hub.adopt_repo(&name)?;
+
+            print_success(&format!("Repository '{}' renamed to: {}", name, new_path.display()));
+            print_warning(&format!(
+                "Remotes pointing at the old path must be updated with 'git remote set-url <remote> {}'",
+                new_path.display()
+            ));
+        }
+
+        Commands::ExportAll { output } => {
+            let hub = LocalGitHub::new(&hub_path).exact_names(exact_names);
+            let (count, size) = hub.export_all(&output)?;
+
+            print_success(&format!("Hub exported to: {}", output.display()));
+            println!("  Repositories: {}", count);
+            println!("  Size:         {}", format_size(size, humansize::DECIMAL));
+        }
+
+        Commands::ImportAll { archive, dest } => {
+            let dest = dest.unwrap_or_else(|| hub_path.clone());
+            let hub = LocalGitHub::new(&dest).exact_names(exact_names);
+            let count = hub.import_all(&archive)?;
+
+            print_success(&format!("Hub restored to: {}", dest.display()));
+            println!("  Repositories: {}", count);
+        }
+
+        Commands::Index { root } => {
+            let root = root.unwrap_or_else(|| hub_path.clone());
+            let hub = LocalGitHub::new(&root).exact_names(exact_names);
+            let index = hub.write_index()?;
+
+            print_success(&format!(
+                "Indexed {} repositories into {}",
+                index.repos.len(),
+                root.join(INDEX_FILE).display()
+            ));
+        }
+
+        Commands::Doctor => {
+            let hub = LocalGitHub::new(&hub_path).exact_names(exact_names);
+            let unsuffixed = hub.find_unsuffixed_repos()?;
+
+            print_header("Hub Check");
+
+            // Missing commit-graphs and reflogs are reported but not counted as problems
+            let repos = hub.list_repos()?;
+            let without_reflog: Vec<&String> = repos
+                .iter()
+                .filter(|name| !hub.reflog_enabled(name).unwrap_or(true))
+                .collect();
+            if !without_reflog.is_empty() {
+                print_info(&format!(
+                    "{} of {} repositories do not record reflogs (overwritten tips cannot be recovered):",
+                    without_reflog.len(),
+                    repos.len()
+                ));
+                for name in &without_reflog {
+                    println!("  {}", name.dimmed());
+                }
+                print_info("Enable with 'git config core.logAllRefUpdates true' inside the repository");
+            }
+
+            let without_graph: Vec<&String> = repos
+                .iter()
+                .filter(|name| !hub.has_commit_graph(name).unwrap_or(false))
+                .collect();
+            if without_graph.is_empty() {
+                print_info(&format!("All {} repositories have a commit-graph", repos.len()));
+            } else {
+                print_info(&format!(
+                    "{} of {} repositories have no commit-graph:",
+                    without_graph.len(),
+                    repos.len()
+                ));
+                for name in &without_graph {
+                    println!("  {}", name.dimmed());
+                }
+                print_info("Use 'local-git-rs commit-graph' to speed up history walks");
+            }
+
+            if unsuffixed.is_empty() {
+                print_success("No problems found");
+            } else {
+                print_warning("Repositories without the .git suffix are hidden from listings:");
+                for name in &unsuffixed {
+                    println!("  {}", name.yellow());
+                }
+                print_info("Use 'local-git-rs adopt <name>' to rename them");
+                anyhow::bail!("{} problems found", unsuffixed.len());
+            }
+        }
+
+        Commands::Fork { source, dest, detach } => {
+            let hub = LocalGitHub::new(&hub_path).exact_names(exact_names);
+
+            if !hub.repo_exists(&source) {
+                print_error(&format!("Repository '{}' does not exist", source));
+                anyhow::bail!("Repository not found");
+            }
+
+            if detach {
+                hub.detach_fork(&source)?;
+                print_success(&format!("Repository '{}' now has its own copy of all objects", source));
+                return Ok(());
+            }
+
+            let dest = dest.expect("dest is required without --detach");
+            let fork_path = hub.fork_repo(&source, &dest)?;
+
+            print_success(&format!("Fork '{}' created at: {}", dest, fork_path.display()));
+            print_warning(&format!(
+                "'{}' borrows objects from '{}': deleting or relocating '{}' will corrupt the fork",
+                dest, source, source
+            ));
+            print_info(&format!("Use 'local-git-rs fork --detach {}' to make it independent", dest));
+        }
+
+        Commands::Relocate { name, to_hub } => {
+            let hub = LocalGitHub::new(&hub_path).exact_names(exact_names);
+
+            if !hub.repo_exists(&name) {
+                print_error(&format!("Repository '{}' does not exist", name));
+                anyhow::bail!("Repository not found");
+            }
+
+            let old_path = hub.get_repo_path(&name)?;
+            let old_path = old_path.canonicalize().unwrap_or(old_path);
+            let dest_hub = LocalGitHub::new(&to_hub);
+            let new_path = hub.relocate_repo(&name, &dest_hub)?;
+
+            print_success(&format!("Repository '{}' moved to: {}", name, new_path.display()));
+            print_warning(&format!(
+                "Remotes pointing at {} must be updated with 'git remote set-url <remote> {}'",
+                old_path.display(),
+                new_path.display()
+            ));
+        }
 
         Commands::AddRemote {
             name,
             remote_name,
             path,
+            auto_rename,
+            set_upstream,
+            relative,
+            url_alias,
+            on_hub_repo,
         } => {
-            let hub = LocalGitHub::new(&hub_path);
-            if !hub.repo_exists(&name) {
-                print_error(&format!("Repository '{}' does not exist in hub", name));
-                print_info(&format!("Use 'local-git-rs create {}' to create it first", name));
-                anyhow::bail!("Repository not found");
+            let hub = LocalGitHub::new(&hub_path).exact_names(exact_names);
+            for repo in std::iter::once(&name).chain(&on_hub_repo) {
+                if !hub.repo_exists(repo) {
+                    print_error(&format!("Repository '{}' does not exist in hub", repo));
+                    print_info(&format!("Use 'local-git-rs create {}' to create it first", repo));
+                    anyhow::bail!("Repository not found");
+                }
             }
 
-            let hub_repo_path = hub.get_repo_path(&name)?;
+            if let Some(source) = &on_hub_repo {
+                let remote_name = hub.add_inter_hub_remote(source, &remote_name, &name, auto_rename)?;
+
+                print_success(&format!(
+                    "Added remote '{}' on '{}' -> {}",
+                    remote_name,
+                    source,
+                    hub.get_repo_path(&name)?.display()
+                ));
+                print_info(&format!(
+                    "Propagate with 'git --git-dir {} push --mirror {}'",
+                    hub.get_repo_path(source)?.display(),
+                    remote_name
+                ));
+                return Ok(());
+            }
+
+            let mut hub_repo_path = hub.get_repo_path(&name)?;
             let path_ref = path.as_deref();
 
-            RemoteManager::add_local_remote(path_ref, &remote_name, &hub_repo_path)?;
+            if relative {
+                hub_repo_path = RemoteManager::relative_hub_path(path_ref, &hub_repo_path)?;
+            }
+
+            if let Some(alias) = &url_alias {
+                RemoteManager::set_url_alias(alias, &hub_repo_path)?;
+                print_success(&format!("Global git config maps '{}' to {}", alias, hub_repo_path.display()));
+                hub_repo_path = PathBuf::from(alias);
+            }
+
+            let remote_name = RemoteManager::add_local_remote(path_ref, &remote_name, &hub_repo_path, auto_rename)?;
 
             print_success(&format!("Added remote '{}' -> {}", remote_name, hub_repo_path.display()));
-            print_info(&format!("Now you can use 'git push {} <branch>' to push to local backup", remote_name));
+
+            if relative {
+                print_warning("Relative remotes break if the repository or the hub moves independently");
+            }
+
+            if set_upstream {
+                match RemoteManager::set_upstream(path_ref, &remote_name) {
+                    Ok(upstream) => {
+                        print_success(&format!("Upstream set to '{}'", upstream));
+                        print_info("Now plain 'git push' and 'git pull' use the local backup");
+                    }
+                    Err(e) => {
+                        print_warning(&format!("Could not set upstream: {}", e));
+                        print_info(&format!("Now you can use 'git push {} <branch>' to push to local backup", remote_name));
+                    }
+                }
+            } else {
+                print_info(&format!("Now you can use 'git push {} <branch>' to push to local backup", remote_name));
+            }
         }
 
         Commands::AddPushUrl {
@@ -306,7 +2080,7 @@ fn main() -> Result<()> {
             remote_name,
             path,
         } => {
-            let hub = LocalGitHub::new(&hub_path);
+            let hub = LocalGitHub::new(&hub_path).exact_names(exact_names);
             if !hub.repo_exists(&name) {
                 print_error(&format!("Repository '{}' does not exist in hub", name));
                 print_info(&format!("Use 'local-git-rs create {}' to create it first", name));
@@ -322,8 +2096,28 @@ fn main() -> Result<()> {
             print_info(&format!("Now every 'git push {}' will also push to local backup", remote_name));
         }
 
-        Commands::ListRemotes { path } => {
+        Commands::ListRemotes { path, resolve } => {
             let path_ref = path.as_deref();
+
+            if resolve {
+                let remotes = RemoteManager::list_remotes_resolved(path_ref)?;
+
+                if remotes.is_empty() {
+                    print_warning("No remotes in current repository");
+                } else {
+                    print_header("Remotes in Current Repository");
+                    for (name, url, resolution) in remotes {
+                        let resolved = match resolution {
+                            UrlResolution::NotLocal => "(not a local path)".dimmed(),
+                            UrlResolution::Exists(path) => path.display().to_string().green(),
+                            UrlResolution::Dangling(path) => format!("{} (dangling)", path.display()).red(),
+                        };
+                        println!("  {} -> {} => {}", name.cyan(), url.dimmed(), resolved);
+                    }
+                }
+                return Ok(());
+            }
+
             let remotes = RemoteManager::list_remotes(path_ref)?;
 
             if remotes.is_empty() {
@@ -338,11 +2132,61 @@ fn main() -> Result<()> {
 
         Commands::RemoveRemote {
             remote_name,
+            matching_hub,
             path,
+            force,
         } => {
             let path_ref = path.as_deref();
-            RemoteManager::remove_remote(path_ref, &remote_name)?;
-            print_success(&format!("Remote '{}' removed", remote_name));
+
+            if matching_hub {
+                let remotes = RemoteManager::find_hub_remotes(path_ref, &hub_path)?;
+
+                if remotes.is_empty() {
+                    print_warning("No remotes point into the hub");
+                    return Ok(());
+                }
+
+                print_warning(&format!("You are about to remove {} remote(s):", remotes.len()));
+                for (name, url) in &remotes {
+                    println!("  {} -> {}", name.cyan(), url.dimmed());
+                }
+
+                if !confirm("Are you sure you want to remove these remotes?", assume_yes)? {
+                    print_info("Removal cancelled");
+                    return Ok(());
+                }
+
+                for name in RemoteManager::remove_hub_remotes(path_ref, &hub_path)? {
+                    print_success(&format!("Remote '{}' removed", name));
+                }
+            } else if let Some(remote_name) = remote_name {
+                // This tool manages hub remotes, so removing anything else is likely a typo
+                let url = RemoteManager::list_remotes(path_ref)?
+                    .into_iter()
+                    .find(|(name, _)| *name == remote_name)
+                    .map(|(_, url)| url);
+                let is_hub_remote = RemoteManager::find_hub_remotes(path_ref, &hub_path)?
+                    .iter()
+                    .any(|(name, _)| *name == remote_name);
+
+                if let Some(url) = url
+                    && !is_hub_remote
+                    && !force
+                {
+                    print_warning(&format!(
+                        "Remote '{}' does not point into the hub: {}",
+                        remote_name,
+                        url.dimmed()
+                    ));
+                    if !confirm(&format!("Remove '{}' -> {} anyway?", remote_name, url), assume_yes)? {
+                        print_info("Removal cancelled");
+                        return Ok(());
+                    }
+                }
+
+                RemoteManager::remove_remote(path_ref, &remote_name)?;
+                print_success(&format!("Remote '{}' removed", remote_name));
+            }
         }
     }
 
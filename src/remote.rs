@@ -1,6 +1,16 @@
 use anyhow::{Context, Result};
 use git2::Repository;
-use std::path::Path;
+use std::path::{Path, PathBuf};
+
+/// Where a remote URL points on the local filesystem
+pub enum UrlResolution {
+    /// Not a local path (e.g., https:// or scp-style ssh URLs)
+    NotLocal,
+    /// Local path that exists, canonicalized
+    Exists(PathBuf),
+    /// Local path that does not exist
+    Dangling(PathBuf),
+}
 
 /// Remote manager
 /// Manages adding and remotes for local repositories
@@ -8,38 +18,45 @@ pub struct RemoteManager;
 
 impl RemoteManager {
     /// Add local remote to current Git repository
+    /// Returns the name of the remote that was added
     ///
     /// # Arguments
     /// * `repo_path` - Current repository path (None for current directory)
     /// * `remote_name` - Remote name (e.g., "local-hub")
     /// * `hub_repo_path` - Path to bare repository in local hub
+    /// * `auto_rename` - On name collision, append a numeric suffix (e.g., "local-hub-2") instead of failing
     pub fn add_local_remote(
         repo_path: Option<&Path>,
         remote_name: &str,
         hub_repo_path: &Path,
-    ) -> Result<()> {
-        let repo = if let Some(path) = repo_path {
-            Repository::open(path)
-                .context("Failed to open repository")?
-        } else {
-            Repository::open_from_env()
-                .context("Failed to open repository from current directory")?
-        };
+        auto_rename: bool,
+    ) -> Result<String> {
+        let repo = Self::open_repo(repo_path)?;
 
         let hub_repo_str = hub_repo_path
             .to_str()
             .context("Hub repo path is not valid UTF-8")?;
 
         // Check if remote already exists
-        if repo.find_remote(remote_name).is_ok() {
-            anyhow::bail!("Remote '{}' already exists", remote_name);
+        let mut chosen_name = remote_name.to_string();
+        if repo.find_remote(&chosen_name).is_ok() {
+            if !auto_rename {
+                anyhow::bail!("Remote '{}' already exists", remote_name);
+            }
+
+            // Probe for the first free numeric suffix
+            let mut suffix = 2;
+            while repo.find_remote(&chosen_name).is_ok() {
+                chosen_name = format!("{}-{}", remote_name, suffix);
+                suffix += 1;
+            }
         }
 
         // Add remote
-        repo.remote(remote_name, hub_repo_str)
+        repo.remote(&chosen_name, hub_repo_str)
             .context("Failed to add remote")?;
 
-        Ok(())
+        Ok(chosen_name)
     }
 
     /// Add extra push URL to existing remote
@@ -54,13 +71,7 @@ impl RemoteManager {
         remote_name: &str,
         hub_repo_path: &Path,
     ) -> Result<()> {
-        let repo = if let Some(path) = repo_path {
-            Repository::open(path)
-                .context("Failed to open repository")?
-        } else {
-            Repository::open_from_env()
-                .context("Failed to open repository from current directory")?
-        };
+        let repo = Self::open_repo(repo_path)?;
 
         let hub_repo_str = hub_repo_path
             .to_str()
@@ -74,10 +85,10 @@ impl RemoteManager {
         let push_url_key = format!("remote.{}.pushurl", remote_name);
 
         // Check if already exists
-        if let Ok(existing) = config.get_string(&push_url_key) {
-            if existing == hub_repo_str {
-                anyhow::bail!("Push URL '{}' already exists for remote '{}'", hub_repo_str, remote_name);
-            }
+        if let Ok(existing) = config.get_string(&push_url_key)
+            && existing == hub_repo_str
+        {
+            anyhow::bail!("Push URL '{}' already exists for remote '{}'", hub_repo_str, remote_name);
         }
 
         // Add new push URL
@@ -91,13 +102,7 @@ impl RemoteManager {
 
     /// List all remotes in current repository
     pub fn list_remotes(repo_path: Option<&Path>) -> Result<Vec<(String, String)>> {
-        let repo = if let Some(path) = repo_path {
-            Repository::open(path)
-                .context("Failed to open repository")?
-        } else {
-            Repository::open_from_env()
-                .context("Failed to open repository from current directory")?
-        };
+        let repo = Self::open_repo(repo_path)?;
 
         let mut remotes = Vec::new();
 
@@ -109,15 +114,15 @@ impl RemoteManager {
             let remote = repo.find_remote(remote)
                 .context("Failed to find remote")?;
 
-            if let Some(name) = remote.name() {
-                if let Some(url) = remote.url() {
-                    remotes.push((name.to_string(), url.to_string()));
-                    // Also show push URL if exists
-                    if let Some(push_url) = remote.pushurl() {
-                        if push_url != url {
-                            remotes.push((format!("{} (push)", name), push_url.to_string()));
-                        }
-                    }
+            if let Some(name) = remote.name()
+                && let Some(url) = remote.url()
+            {
+                remotes.push((name.to_string(), url.to_string()));
+                // Also show push URL if exists
+                if let Some(push_url) = remote.pushurl()
+                    && push_url != url
+                {
+                    remotes.push((format!("{} (push)", name), push_url.to_string()));
                 }
             }
         }
@@ -127,6 +132,201 @@ impl RemoteManager {
 
     /// Delete remote
     pub fn remove_remote(repo_path: Option<&Path>, remote_name: &str) -> Result<()> {
+        let repo = Self::open_repo(repo_path)?;
+
+        repo.remote_delete(remote_name)
+            .context(format!("Failed to delete remote '{}'", remote_name))?;
+
+        Ok(())
+    }
+
+    /// Find remotes whose fetch or push URL points into the hub
+    /// Returns (remote name, URL) pairs
+    pub fn find_hub_remotes(repo_path: Option<&Path>, hub_path: &Path) -> Result<Vec<(String, String)>> {
+        let repo = Self::open_repo(repo_path)?;
+        let base = repo.workdir().unwrap_or_else(|| repo.path()).to_path_buf();
+
+        let mut matches = Vec::new();
+
+        for name in repo.remotes()
+            .context("Failed to list remotes")?
+            .iter()
+            .flatten()
+        {
+            let remote = repo.find_remote(name)
+                .context("Failed to find remote")?;
+
+            let hub_url = [remote.url(), remote.pushurl()]
+                .into_iter()
+                .flatten()
+                .find(|url| Self::url_in_hub(&base, url, hub_path));
+
+            if let Some(url) = hub_url {
+                matches.push((name.to_string(), url.to_string()));
+            }
+        }
+
+        Ok(matches)
+    }
+
+    /// List all remotes with where each URL resolves on the local filesystem
+    /// Relative URLs are resolved against the repository's top-level directory, `~/` against $HOME
+    pub fn list_remotes_resolved(repo_path: Option<&Path>) -> Result<Vec<(String, String, UrlResolution)>> {
+        let repo = Self::open_repo(repo_path)?;
+        let base = repo.workdir().unwrap_or_else(|| repo.path()).to_path_buf();
+
+        let remotes = Self::list_remotes(repo_path)?
+            .into_iter()
+            .map(|(name, url)| {
+                let resolution = match Self::local_path(&base, &url) {
+                    None => UrlResolution::NotLocal,
+                    Some(path) => match path.canonicalize() {
+                        Ok(path) => UrlResolution::Exists(path),
+                        Err(_) => UrlResolution::Dangling(path),
+                    },
+                };
+                (name, url, resolution)
+            })
+            .collect();
+
+        Ok(remotes)
+    }
+
+    /// Remove all remotes whose fetch or push URL points into the hub
+    /// Returns the names of the removed remotes
+    pub fn remove_hub_remotes(repo_path: Option<&Path>, hub_path: &Path) -> Result<Vec<String>> {
+        let repo = Self::open_repo(repo_path)?;
+        let mut removed = Vec::new();
+
+        for (name, _) in Self::find_hub_remotes(repo_path, hub_path)? {
+            repo.remote_delete(&name)
+                .context(format!("Failed to delete remote '{}'", name))?;
+            removed.push(name);
+        }
+
+        Ok(removed)
+    }
+
+    /// Set upstream of the current branch to `<remote_name>/<branch>`
+    /// Returns the configured upstream (e.g., "local-hub/main")
+    pub fn set_upstream(repo_path: Option<&Path>, remote_name: &str) -> Result<String> {
+        let repo = Self::open_repo(repo_path)?;
+
+        if repo.head_detached().unwrap_or(false) {
+            anyhow::bail!("HEAD is detached, check out a branch to set its upstream");
+        }
+
+        let head = match repo.head() {
+            Ok(head) => head,
+            Err(e) if e.code() == git2::ErrorCode::UnbornBranch => {
+                anyhow::bail!("Current branch has no commits yet, commit first and then run 'git push -u {} <branch>'", remote_name);
+            }
+            Err(e) => return Err(e).context("Failed to resolve HEAD"),
+        };
+
+        let branch = head
+            .shorthand()
+            .context("Current branch name is not valid UTF-8")?
+            .to_string();
+
+        // Configure tracking directly: the remote-tracking ref doesn't exist until the first push/fetch
+        let mut config = repo.config().context("Failed to open config")?;
+        config.set_str(&format!("branch.{}.remote", branch), remote_name)
+            .context("Failed to set upstream remote")?;
+        config.set_str(&format!("branch.{}.merge", branch), &format!("refs/heads/{}", branch))
+            .context("Failed to set upstream branch")?;
+
+        Ok(format!("{}/{}", remote_name, branch))
+    }
+
+    /// Compute hub repository path relative to the working repository's top-level directory
+    /// The result is verified to resolve back to the hub repository
+    pub fn relative_hub_path(repo_path: Option<&Path>, hub_repo_path: &Path) -> Result<PathBuf> {
+        let repo = Self::open_repo(repo_path)?;
+        let base = repo.workdir().unwrap_or_else(|| repo.path());
+
+        let base = base.canonicalize()
+            .context("Failed to resolve repository directory")?;
+        let target = hub_repo_path.canonicalize()
+            .context("Failed to resolve hub repository path")?;
+
+        let relative = pathdiff::diff_paths(&target, &base)
+            .context("Cannot express hub repository path relative to repository")?;
+
+        // Make sure the relative path round-trips before storing it
+        let resolved = base.join(&relative).canonicalize()
+            .context("Relative hub repository path does not resolve")?;
+        if resolved != target {
+            anyhow::bail!(
+                "Relative path '{}' resolves to '{}' instead of '{}'",
+                relative.display(),
+                resolved.display(),
+                target.display()
+            );
+        }
+
+        Ok(relative)
+    }
+
+    /// Map a logical URL alias to the hub repository in the user's global git config
+    /// Writes `url.<hub repo path>.insteadOf = <alias>`, so remotes can store the alias
+    /// and each machine rewrites it to its own hub location
+    pub fn set_url_alias(alias: &str, hub_repo_path: &Path) -> Result<()> {
+        let hub_repo_str = hub_repo_path
+            .to_str()
+            .context("Hub repo path is not valid UTF-8")?;
+
+        let mut config = git2::Config::open_default()
+            .and_then(|mut config| config.open_global())
+            .context("Failed to open global git config")?;
+
+        // insteadOf is multi-valued; replace only an identical existing mapping
+        let key = format!("url.{}.insteadOf", hub_repo_str);
+        config.set_multivar(&key, &format!("^{}$", regex::escape(alias)), alias)
+            .context("Failed to write insteadOf mapping")?;
+
+        Ok(())
+    }
+
+    /// Check if a remote URL is a local path inside the hub
+    /// Relative URLs are resolved against the repository's top-level directory
+    fn url_in_hub(base: &Path, url: &str, hub_path: &Path) -> bool {
+        let Some(target) = Self::local_path(base, url) else {
+            return false;
+        };
+
+        let target = target.canonicalize().unwrap_or(target);
+        let hub = hub_path.canonicalize().unwrap_or_else(|_| hub_path.to_path_buf());
+
+        target.starts_with(&hub)
+    }
+
+    /// Interpret a remote URL as a local path, or None for network URLs
+    fn local_path(base: &Path, url: &str) -> Option<PathBuf> {
+        let local = url.strip_prefix("file://").unwrap_or(url);
+        if local.contains("://") {
+            return None;
+        }
+
+        // scp-style "host:path" (a colon before any slash) is ssh, not a path
+        if let Some((host, _)) = local.split_once(':')
+            && !host.contains('/')
+            && host.len() > 1
+        {
+            return None;
+        }
+
+        if let Some(rest) = local.strip_prefix("~/")
+            && let Ok(home) = std::env::var("HOME")
+        {
+            return Some(PathBuf::from(home).join(rest));
+        }
+
+        Some(base.join(local))
+    }
+
+    /// Open repository at path, or discover it from the current directory
+    fn open_repo(repo_path: Option<&Path>) -> Result<Repository> {
         let repo = if let Some(path) = repo_path {
             Repository::open(path)
                 .context("Failed to open repository")?
@@ -135,9 +335,6 @@ impl RemoteManager {
                 .context("Failed to open repository from current directory")?
         };
 
-        repo.remote_delete(remote_name)
-            .context(format!("Failed to delete remote '{}'", remote_name))?;
-
-        Ok(())
+        Ok(repo)
     }
-}
\ No newline at end of file
+}
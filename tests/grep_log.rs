@@ -0,0 +1,43 @@
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+
+use git2::{Repository, Signature};
+
+/// Create a bare repository in the hub with one commit on master
+fn create_repo(hub: &Path, name: &str, message: &str) {
+    let repo = Repository::init_bare(hub.join(name)).unwrap();
+    let signature = Signature::now("Test", "test@example.com").unwrap();
+    let tree_id = repo.treebuilder(None).unwrap().write().unwrap();
+    let tree = repo.find_tree(tree_id).unwrap();
+    repo.commit(Some("refs/heads/master"), &signature, &signature, message, &tree, &[])
+        .unwrap();
+}
+
+#[test]
+fn grep_log_skips_corrupted_repository() {
+    let hub = tempfile::tempdir().unwrap();
+    create_repo(hub.path(), "good.git", "fix the parser");
+    create_repo(hub.path(), "broken.git", "fix the lexer");
+
+    // A ref pointing at an object that does not exist
+    fs::write(
+        hub.path().join("broken.git/refs/heads/dangling"),
+        format!("{}\n", "1".repeat(40)),
+    )
+    .unwrap();
+
+    let output = Command::new(env!("CARGO_BIN_EXE_local-git-rs"))
+        .arg("--hub-path")
+        .arg(hub.path())
+        .args(["grep-log", "fix"])
+        .output()
+        .unwrap();
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(output.status.success(), "{}", stderr);
+    assert!(stdout.contains("good.git"), "{}", stdout);
+    assert!(stdout.contains("fix the parser"), "{}", stdout);
+    assert!(stderr.contains("Skipping 'broken.git'"), "{}", stderr);
+}